@@ -0,0 +1,135 @@
+//! Generates a test per part for every example fixture.
+//!
+//! Fixtures live in `input/2020/examples/day<N>/<name>.txt`, and each day's
+//! directory has an `answers.toml` with a `[<name>]` table holding the
+//! expected `part1` and/or `part2` answer for that fixture.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EXAMPLES_DIR: &str = "input/2020/examples";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", EXAMPLES_DIR);
+
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join(EXAMPLES_DIR);
+    let mut tests = String::new();
+    for (day, dir) in day_dirs(&root) {
+        for test in day_tests(day, &dir) {
+            tests.push_str(&test);
+        }
+    }
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(out, tests).unwrap();
+}
+
+/// The `day<N>` directories under `root`, ordered by day.
+fn day_dirs(root: &Path) -> Vec<(u32, PathBuf)> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut days = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .map(|path| {
+            let day = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("day"))
+                .and_then(|day| day.parse().ok())
+                .unwrap_or_else(|| panic!("`{}` is not a `day<N>` directory", path.display()));
+            (day, path)
+        })
+        .collect::<Vec<_>>();
+    days.sort();
+    days
+}
+
+fn day_tests(day: u32, dir: &Path) -> Vec<String> {
+    let answers_path = dir.join("answers.toml");
+    let answers = fs::read_to_string(&answers_path)
+        .unwrap_or_else(|e| panic!("could not read `{}`: {}", answers_path.display(), e))
+        .parse::<toml::Value>()
+        .unwrap_or_else(|e| panic!("invalid `{}`: {}", answers_path.display(), e));
+    let answers = answers
+        .as_table()
+        .unwrap_or_else(|| panic!("`{}` must be a table", answers_path.display()));
+
+    let mut fixtures = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect::<Vec<_>>();
+    fixtures.sort();
+    for fixture in &fixtures {
+        let name = fixture.file_stem().unwrap().to_str().unwrap();
+        assert!(
+            answers.contains_key(name),
+            "`{}` has no `[{}]` answers in `{}`",
+            fixture.display(),
+            name,
+            answers_path.display()
+        );
+    }
+
+    let mut tests = vec![];
+    for (name, parts) in answers {
+        let fixture = dir.join(format!("{}.txt", name));
+        assert!(
+            fixture.exists(),
+            "`{}` has answers for `{}`, which does not exist",
+            answers_path.display(),
+            fixture.display()
+        );
+        let parts = parts
+            .as_table()
+            .unwrap_or_else(|| panic!("`{}` must be a table", name));
+        for (part, answer) in parts {
+            let (number, variant) = match part.as_str() {
+                "part1" => (1, "One"),
+                "part2" => (2, "Two"),
+                _ => panic!("unknown key `{}.{}` for day {}", name, part, day),
+            };
+            let answer = match answer {
+                toml::Value::String(answer) => answer.clone(),
+                toml::Value::Integer(answer) => answer.to_string(),
+                _ => panic!("`{}.{}` must be a string or an integer", name, part),
+            };
+
+            let mut test = String::new();
+            writeln!(
+                test,
+                "#[test]\nfn day{}_{}_part{}() {{\n    super::check_example({}, include_str!({:?}), crate::runner::Part::{}, {:?});\n}}\n",
+                day,
+                identifier(name),
+                number,
+                day,
+                fixture.display().to_string(),
+                variant,
+                answer
+            )
+            .unwrap();
+            tests.push(test);
+        }
+    }
+
+    tests
+}
+
+/// Turns a fixture name into something usable in a test name.
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,238 @@
+use crate::runner::{Day, Part};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::fs;
+
+/// Where the accepted answers for the real puzzle inputs are recorded.
+pub const ANSWERS_PATH: &str = "input/2020/answers.toml";
+
+/// Accepted answers, keyed by day. Each `[dayN]` table may have a `part1`
+/// and a `part2` entry, given as either a string or an integer.
+#[derive(Default)]
+pub struct Answers {
+    days: BTreeMap<u32, [Option<String>; 2]>,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let value = input
+            .parse::<toml::Value>()
+            .map_err(|e| format!("invalid answers file: {}", e))?;
+        let table = value.as_table().ok_or("answers must be a table")?;
+
+        let mut days = BTreeMap::new();
+        for (key, value) in table {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| format!("expected a `dayN` table, found `{}`", key))?;
+            let parts = value
+                .as_table()
+                .ok_or_else(|| format!("`{}` must be a table", key))?;
+
+            let mut answers = [None, None];
+            for (part, answer) in parts {
+                let index = match part.as_str() {
+                    "part1" => 0,
+                    "part2" => 1,
+                    _ => return Err(format!("unknown key `{}.{}`", key, part)),
+                };
+                answers[index] = Some(match answer {
+                    toml::Value::String(answer) => answer.clone(),
+                    toml::Value::Integer(answer) => answer.to_string(),
+                    _ => return Err(format!("`{}.{}` must be a string or an integer", key, part)),
+                });
+            }
+            days.insert(day, answers);
+        }
+
+        Ok(Self { days })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let input =
+            fs::read_to_string(path).map_err(|e| format!("could not read `{}`: {}", path, e))?;
+        Self::parse(&input)
+    }
+
+    pub fn expected(&self, day: u32, part: Part) -> Option<&str> {
+        self.days
+            .get(&day)
+            .and_then(|answers| answers[part.number() as usize - 1].as_deref())
+    }
+}
+
+pub enum Status {
+    Ok,
+    Mismatch,
+    /// No accepted answer has been recorded yet.
+    Unknown,
+    /// The day could not be run at all.
+    Failed,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Status::Ok => "ok",
+            Status::Mismatch => "MISMATCH",
+            Status::Unknown => "unknown",
+            Status::Failed => "FAILED",
+        })
+    }
+}
+
+/// The outcome of comparing one part against its accepted answer.
+pub struct Check {
+    pub day: u32,
+    pub part: Part,
+    pub expected: Option<String>,
+    /// The computed answer, or why it could not be computed.
+    pub actual: Result<String, String>,
+}
+
+impl Check {
+    pub fn status(&self) -> Status {
+        match (&self.expected, &self.actual) {
+            (_, Err(_)) => Status::Failed,
+            (None, Ok(_)) => Status::Unknown,
+            (Some(expected), Ok(actual)) if expected == actual => Status::Ok,
+            (Some(_), Ok(_)) => Status::Mismatch,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        matches!(self.status(), Status::Ok | Status::Unknown)
+    }
+}
+
+/// Runs both parts of `day` against `input` and compares them with `answers`.
+/// An `Err` input (e.g. a missing file) is reported as a failure of both parts.
+pub fn verify(day: &Day, input: Result<String, String>, answers: &Answers) -> Vec<Check> {
+    let actual = input.and_then(|input| {
+        day.run(&input, &Part::ALL)
+            .map(|report| {
+                report
+                    .answers
+                    .into_iter()
+                    .map(|answer| answer.answer)
+                    .collect::<Vec<_>>()
+            })
+            .map_err(|e| e.to_string())
+    });
+
+    Part::ALL
+        .iter()
+        .map(|&part| Check {
+            day: day.number(),
+            part,
+            expected: answers.expected(day.number(), part).map(String::from),
+            actual: match &actual {
+                Ok(answers) => answers[part.number() as usize - 1].clone(),
+                Err(e) => Err(e.clone()),
+            },
+        })
+        .collect()
+}
+
+/// Renders checks as a table, one row per part.
+pub struct Table<'a>(pub &'a [Check]);
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn actual(check: &Check) -> &str {
+            match &check.actual {
+                Ok(actual) => actual,
+                Err(_) => "-",
+            }
+        }
+
+        let expected_width = self
+            .0
+            .iter()
+            .map(|check| check.expected.as_deref().unwrap_or("-").len())
+            .chain(Some("Expected".len()))
+            .max()
+            .unwrap();
+        let actual_width = self
+            .0
+            .iter()
+            .map(|check| actual(check).len())
+            .chain(Some("Actual".len()))
+            .max()
+            .unwrap();
+
+        writeln!(
+            f,
+            "Day | Part | {:ew$} | {:aw$} | Status",
+            "Expected",
+            "Actual",
+            ew = expected_width,
+            aw = actual_width
+        )?;
+        writeln!(
+            f,
+            "----|------|-{:-<ew$}-|-{:-<aw$}-|-------",
+            "",
+            "",
+            ew = expected_width,
+            aw = actual_width
+        )?;
+        for check in self.0 {
+            writeln!(
+                f,
+                "{:>3} | {:>4} | {:ew$} | {:aw$} | {}",
+                check.day,
+                check.part.number(),
+                check.expected.as_deref().unwrap_or("-"),
+                actual(check),
+                check.status(),
+                ew = expected_width,
+                aw = actual_width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse() {
+    let answers = Answers::parse(
+        r#"[day1]
+part1 = "889779"
+part2 = 76110336
+
+[day2]
+part1 = "622"
+"#,
+    )
+    .unwrap();
+    assert_eq!(answers.expected(1, Part::One), Some("889779"));
+    assert_eq!(answers.expected(1, Part::Two), Some("76110336"));
+    assert_eq!(answers.expected(2, Part::Two), None);
+    assert_eq!(answers.expected(3, Part::One), None);
+    assert!(Answers::parse("[day1]\npart3 = 1").is_err());
+    assert!(Answers::parse("[one]\npart1 = 1").is_err());
+}
+
+#[test]
+fn test_verify() {
+    let answers = Answers::parse("[day8]\npart1 = 5\npart2 = 9").unwrap();
+    let day = crate::runner::find(8).unwrap();
+    let content = "nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+    let checks = verify(day, Ok(content.into()), &answers);
+    assert!(matches!(checks[0].status(), Status::Ok));
+    assert!(matches!(checks[1].status(), Status::Mismatch));
+
+    let checks = verify(day, Err("missing".into()), &answers);
+    assert!(checks.iter().all(|check| !check.passed()));
+}
@@ -0,0 +1,262 @@
+use crate::params::Params;
+use crate::parse::ParseError;
+use crate::runner::{Day, Part};
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+/// Summary of repeated timings of a single step.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarizes `samples`, which must not be empty.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "need at least one sample");
+        samples.sort();
+        // Nearest-rank percentiles, so every value is an actual sample.
+        let rank = |percentile: usize| {
+            let rank = (percentile * samples.len()).div_ceil(100);
+            samples[rank.max(1) - 1]
+        };
+        Self {
+            min: samples[0],
+            median: rank(50),
+            p95: rank(95),
+        }
+    }
+
+    fn to_json(self) -> String {
+        format!(
+            r#"{{"min_ns":{},"median_ns":{},"p95_ns":{}}}"#,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.p95.as_nanos()
+        )
+    }
+}
+
+/// Timings for solving one part, with a variant if they were compared.
+pub struct PartStats {
+    pub part: Part,
+    pub variant: Option<&'static str>,
+    pub stats: Stats,
+}
+
+impl PartStats {
+    /// The key for these timings in JSON, like `part1` or `part1/hashing`.
+    fn key(&self) -> String {
+        match self.variant {
+            Some(variant) => format!("part{}/{}", self.part.number(), variant),
+            None => format!("part{}", self.part.number()),
+        }
+    }
+
+    /// The step these timings are for in a table, like `part 1 (hashing)`.
+    fn step(&self) -> String {
+        match self.variant {
+            Some(variant) => format!("part {} ({})", self.part.number(), variant),
+            None => format!("part {}", self.part.number()),
+        }
+    }
+}
+
+/// Timings for one day, with generating the input measured apart from
+/// solving each part.
+pub struct Benchmark {
+    pub day: u32,
+    pub iterations: usize,
+    pub generator: Stats,
+    pub parts: Vec<PartStats>,
+}
+
+impl Benchmark {
+    /// Runs the generator and then each of `parts` `iterations` times. With
+    /// `variants`, every variant of each part is timed instead of just the
+    /// default solution.
+    pub fn run(
+        day: &Day,
+        input: &str,
+        parts: &[Part],
+        params: &Params,
+        iterations: usize,
+        variants: bool,
+    ) -> Result<Self, ParseError> {
+        assert!(iterations > 0, "need at least one iteration");
+
+        let mut samples = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let start = Instant::now();
+            let solver = day.parse_with(input, params.clone())?;
+            samples.push(start.elapsed());
+            drop(solver);
+        }
+        let generator = Stats::from_samples(samples);
+
+        let solver = day.parse_with(input, params.clone())?;
+        let steps = parts.iter().flat_map(|&part| {
+            let names = if variants {
+                solver.variants(part).into_iter().map(Some).collect()
+            } else {
+                vec![None]
+            };
+            names.into_iter().map(move |variant| (part, variant))
+        });
+        let parts = steps
+            .map(|(part, variant)| {
+                let samples = (0..iterations)
+                    .map(|_| {
+                        let start = Instant::now();
+                        let answer = match variant {
+                            Some(variant) => solver.solve_variant(part, variant).unwrap(),
+                            None => solver.solve(part),
+                        };
+                        let elapsed = start.elapsed();
+                        drop(answer);
+                        elapsed
+                    })
+                    .collect();
+                PartStats {
+                    part,
+                    variant,
+                    stats: Stats::from_samples(samples),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            day: day.number(),
+            iterations,
+            generator,
+            parts,
+        })
+    }
+
+    fn to_json(&self) -> String {
+        let parts = self
+            .parts
+            .iter()
+            .map(|part| format!(r#""{}":{}"#, part.key(), part.stats.to_json()))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            r#"{{"day":{},"iterations":{},"generator":{},{}}}"#,
+            self.day,
+            self.iterations,
+            self.generator.to_json(),
+            parts
+        )
+    }
+}
+
+/// Renders benchmarks as a JSON array, with all timings in nanoseconds.
+pub struct Json<'a>(pub &'a [Benchmark]);
+
+impl Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = self
+            .0
+            .iter()
+            .map(Benchmark::to_json)
+            .collect::<Vec<_>>()
+            .join(",\n  ");
+        writeln!(f, "[\n  {}\n]", days)
+    }
+}
+
+/// Renders benchmarks as a table with one row per generator or part.
+pub struct Table<'a>(pub &'a [Benchmark]);
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .0
+            .iter()
+            .flat_map(|benchmark| &benchmark.parts)
+            .map(|part| part.step().len())
+            .chain(Some("generator".len()))
+            .max()
+            .unwrap_or(0);
+        let row = |f: &mut fmt::Formatter<'_>, day: u32, step: &str, stats: &Stats| {
+            writeln!(
+                f,
+                "{:>3} | {:w$} | {:>12} | {:>12} | {:>12}",
+                day,
+                step,
+                format!("{:.2?}", stats.min),
+                format!("{:.2?}", stats.median),
+                format!("{:.2?}", stats.p95),
+                w = width
+            )
+        };
+
+        writeln!(
+            f,
+            "Day | {:w$} | {:>12} | {:>12} | {:>12}",
+            "Step",
+            "min",
+            "median",
+            "p95",
+            w = width
+        )?;
+        writeln!(
+            f,
+            "----|-{:-<w$}-|--------------|--------------|-------------",
+            "",
+            w = width
+        )?;
+        for benchmark in self.0 {
+            row(f, benchmark.day, "generator", &benchmark.generator)?;
+            for part in &benchmark.parts {
+                row(f, benchmark.day, &part.step(), &part.stats)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stats() {
+    let samples = (1..=20).map(Duration::from_millis).collect::<Vec<_>>();
+    let stats = Stats::from_samples(samples.into_iter().rev().collect());
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.median, Duration::from_millis(10));
+    assert_eq!(stats.p95, Duration::from_millis(19));
+
+    let stats = Stats::from_samples(vec![Duration::from_millis(3)]);
+    assert_eq!(stats.median, Duration::from_millis(3));
+    assert_eq!(stats.p95, Duration::from_millis(3));
+}
+
+#[test]
+fn test_json() {
+    let stats = Stats::from_samples(vec![Duration::from_nanos(5)]);
+    let benchmark = Benchmark {
+        day: 8,
+        iterations: 1,
+        generator: stats,
+        parts: vec![
+            PartStats {
+                part: Part::Two,
+                variant: None,
+                stats,
+            },
+            PartStats {
+                part: Part::Two,
+                variant: Some("sieve"),
+                stats,
+            },
+        ],
+    };
+    assert_eq!(
+        Json(&[benchmark]).to_string(),
+        r#"[
+  {"day":8,"iterations":1,"generator":{"min_ns":5,"median_ns":5,"p95_ns":5},"part2":{"min_ns":5,"median_ns":5,"p95_ns":5},"part2/sieve":{"min_ns":5,"median_ns":5,"p95_ns":5}}
+]
+"#
+    );
+}
@@ -1,65 +1,609 @@
+use crate::params::{Param, Params};
+use crate::parse::{self, ParseError};
+use crate::runner::{Part, Solution, Variant};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+pub const PARAMS: &[Param] = &[Param::new::<i64>(
+    "target",
+    "2020",
+    "the sum the entries must add up to",
+)];
+
+/// Entries summing to a target, with where each one appears in the input.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KSum {
+    /// The entries, smallest first.
+    pub entries: Vec<i64>,
+    /// The position of each entry in the input, which is its 0-based line.
+    pub indices: Vec<usize>,
+}
+
+impl KSum {
+    pub fn product(&self) -> Result<i64, Overflow> {
+        product(&self.entries)
+    }
+}
+
+/// The product of some entries doesn't fit in 64 bits.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Overflow {
+    pub entries: Vec<i64>,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self
+            .entries
+            .iter()
+            .map(i64::to_string)
+            .collect::<Vec<_>>()
+            .join(" * ");
+        write!(f, "the product {} overflows a 64-bit integer", entries)
+    }
+}
+
+impl Error for Overflow {}
+
+/// Why a part of day 1 has no answer.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SolveError {
+    /// No `k` entries sum to the target.
+    NotFound {
+        k: usize,
+        target: i64,
+    },
+    Overflow(Overflow),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NotFound { k, target } => {
+                write!(f, "no {} entries sum to {}", k, target)
+            }
+            SolveError::Overflow(overflow) => overflow.fmt(f),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+impl From<Overflow> for SolveError {
+    fn from(overflow: Overflow) -> Self {
+        SolveError::Overflow(overflow)
+    }
+}
+
+/// Multiplies `entries`, listing them smallest first if that overflows so every
+/// solution reports the same error.
+fn product(entries: &[i64]) -> Result<i64, Overflow> {
+    entries
+        .iter()
+        .try_fold(1i64, |product, &entry| product.checked_mul(entry))
+        .ok_or_else(|| {
+            let mut entries = entries.to_vec();
+            entries.sort_unstable();
+            Overflow { entries }
+        })
+}
 
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Vec<u32> {
-    let mut input = input
-        .lines()
-        .map(|l| l.trim().parse().unwrap())
-        .collect::<Vec<_>>();
-    input.sort();
-    input
+pub fn try_input_generator(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::lines(1, input)
+        .map(|l| l.parse(l.text().trim(), "a 64-bit integer"))
+        .collect()
 }
 
-#[aoc(day1, part1)]
-pub fn solve_part1(input: &[u32]) -> u32 {
-    let (mut left, mut right) = (0, 1);
-    while left < input.len() - 1 && right < input.len() {
-        let left_val = input[left];
-        let right_val = input[right];
-        let sum = left_val + right_val;
-        if sum == 2020 {
-            return left_val * right_val;
+pub fn input_generator(input: &str) -> Vec<i64> {
+    try_input_generator(input).unwrap()
+}
+
+/// Finds `k` distinct entries, by position, that sum to `target`. Of several
+/// such sets, the one whose smallest entries are smallest is returned.
+pub fn find_k_sum(entries: &[i64], k: usize, target: i64) -> Option<KSum> {
+    /// Searches `sorted` for `k` entries summing to `target`, pushing them
+    /// onto `chosen` if found.
+    fn search(
+        sorted: &[(i64, usize)],
+        k: usize,
+        target: i128,
+        chosen: &mut Vec<(i64, usize)>,
+    ) -> bool {
+        match k {
+            0 => target == 0,
+            1 => match sorted.binary_search_by_key(&target, |&(entry, _)| entry as i128) {
+                Ok(i) => {
+                    // Take the first of equal entries, so indices are stable.
+                    let first = sorted[..i].partition_point(|&(entry, _)| (entry as i128) < target);
+                    chosen.push(sorted[first]);
+                    true
+                }
+                Err(_) => false,
+            },
+            2 => {
+                let (mut left, mut right) = (0, sorted.len());
+                while left + 1 < right {
+                    let sum = sorted[left].0 as i128 + sorted[right - 1].0 as i128;
+                    if sum == target {
+                        chosen.extend(&[sorted[left], sorted[right - 1]]);
+                        return true;
+                    }
+                    if sum < target {
+                        left += 1;
+                    } else {
+                        right -= 1;
+                    }
+                }
+                false
+            }
+            _ => {
+                for (i, &(entry, _)) in sorted.iter().enumerate() {
+                    // Every entry from here on is at least as big.
+                    if entry as i128 * k as i128 > target {
+                        break;
+                    }
+                    // A repeated entry can only find what the first one did.
+                    if i > 0 && sorted[i - 1].0 == entry {
+                        continue;
+                    }
+
+                    chosen.push(sorted[i]);
+                    if search(&sorted[i + 1..], k - 1, target - entry as i128, chosen) {
+                        return true;
+                    }
+                    chosen.pop();
+                }
+                false
+            }
+        }
+    }
+
+    let mut sorted = entries.iter().copied().zip(0..).collect::<Vec<_>>();
+    sorted.sort_unstable();
+
+    let mut chosen = Vec::with_capacity(k);
+    if !search(&sorted, k, target as i128, &mut chosen) {
+        return None;
+    }
+
+    chosen.sort_unstable();
+    Some(KSum {
+        entries: chosen.iter().map(|&(entry, _)| entry).collect(),
+        indices: chosen.iter().map(|&(_, index)| index).collect(),
+    })
+}
+
+/// Every set of `k` distinct entries, by position, that sums to `target`,
+/// with equal entries on different lines making different sets. Sets come in
+/// order of their entries, smallest first.
+pub fn k_sums(entries: &[i64], k: usize, target: i64) -> KSums {
+    let mut sorted = entries.iter().copied().zip(0..).collect::<Vec<_>>();
+    sorted.sort_unstable();
+    KSums {
+        sorted,
+        k,
+        target: target as i128,
+        chosen: Vec::with_capacity(k),
+        sum: 0,
+        cursor: 0,
+        done: false,
+    }
+}
+
+/// An iterator over the sets of entries summing to a target, returned by
+/// [`k_sums`].
+pub struct KSums {
+    sorted: Vec<(i64, usize)>,
+    k: usize,
+    target: i128,
+    /// Positions in `sorted` of the set being built, in increasing order.
+    chosen: Vec<usize>,
+    sum: i128,
+    /// The next position to try adding to `chosen`.
+    cursor: usize,
+    done: bool,
+}
+
+impl KSums {
+    /// Drops the last chosen entry to try the ones after it instead, returning
+    /// false once there are none to drop.
+    fn backtrack(&mut self) -> bool {
+        match self.chosen.pop() {
+            Some(position) => {
+                self.sum -= self.sorted[position].0 as i128;
+                self.cursor = position + 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Iterator for KSums {
+    type Item = KSum;
+
+    fn next(&mut self) -> Option<KSum> {
+        while !self.done {
+            if self.chosen.len() == self.k {
+                let found = if self.sum == self.target {
+                    let chosen = self.chosen.iter().map(|&position| self.sorted[position]);
+                    Some(KSum {
+                        entries: chosen.clone().map(|(entry, _)| entry).collect(),
+                        indices: chosen.map(|(_, index)| index).collect(),
+                    })
+                } else {
+                    None
+                };
+                self.done = !self.backtrack();
+                if found.is_some() {
+                    return found;
+                }
+                continue;
+            }
+
+            // Every entry after the cursor is at least as big, so stop once the
+            // set can't be filled or would overshoot.
+            let remaining = self.k - self.chosen.len();
+            let fits = self.sorted.get(self.cursor).is_some_and(|&(entry, _)| {
+                self.cursor + remaining <= self.sorted.len()
+                    && self.sum + entry as i128 * remaining as i128 <= self.target
+            });
+            if fits {
+                self.chosen.push(self.cursor);
+                self.sum += self.sorted[self.cursor].0 as i128;
+                self.cursor += 1;
+            } else {
+                self.done = !self.backtrack();
+            }
+        }
+
+        None
+    }
+}
+
+/// Counts the sets [`k_sums`] would return without listing them, by choosing
+/// how many copies of each distinct entry to use. Returns `None` if there are
+/// more than fit in a `u64`.
+pub fn count_k_sums(entries: &[i64], k: usize, target: i64) -> Option<u64> {
+    /// `n` choose `k`, or `None` if that overflows. Each step's result is
+    /// itself a binomial coefficient, so the division is exact.
+    fn binomial(n: u64, k: u64) -> Option<u128> {
+        (0..k).try_fold(1u128, |result, i| {
+            Some(result.checked_mul((n - i) as u128)? / (i as u128 + 1))
+        })
+    }
+
+    /// Counts the sets of `k` entries from `groups` of equal entries.
+    fn count(groups: &[(i128, u64)], k: u64, target: i128) -> Option<u128> {
+        if k == 0 {
+            return Some((target == 0) as u128);
+        }
+
+        let mut total = 0u128;
+        for (i, &(entry, copies)) in groups.iter().enumerate() {
+            // Sets counted from here on have this as their smallest entry.
+            if entry * k as i128 > target {
+                break;
+            }
+            for used in 1..=copies.min(k) {
+                let rest = count(&groups[i + 1..], k - used, target - entry * used as i128)?;
+                if rest > 0 {
+                    total = total.checked_add(binomial(copies, used)?.checked_mul(rest)?)?;
+                }
+            }
         }
+        Some(total)
+    }
 
-        if sum > 2020 {
-            left += 1;
-            right = left + 1;
+    let mut sorted = entries.to_vec();
+    sorted.sort_unstable();
+    let mut groups = Vec::<(i128, u64)>::new();
+    for entry in sorted {
+        match groups.last_mut() {
+            Some((last, copies)) if *last == entry as i128 => *copies += 1,
+            _ => groups.push((entry as i128, 1)),
+        }
+    }
+
+    count(&groups, k as u64, target as i128).and_then(|total| u64::try_from(total).ok())
+}
+
+/// Finds the fewest entries, by position, that sum to `target`, or `None` if no
+/// subset of the entries does. Sums nothing to reach 0.
+///
+/// Works through the entries keeping the fewest needed to reach each sum, and
+/// drops sums the remaining entries can't bring back to the target, so its
+/// cost is bounded by the number of distinct sums in reach.
+pub fn min_subset_sum(entries: &[i64], target: i64) -> Option<KSum> {
+    let target = target as i128;
+    let mut below = entries
+        .iter()
+        .filter(|&&entry| entry < 0)
+        .map(|&entry| entry as i128)
+        .sum::<i128>();
+    let mut above = entries
+        .iter()
+        .filter(|&&entry| entry > 0)
+        .map(|&entry| entry as i128)
+        .sum::<i128>();
+
+    // `fewest[i]` maps each sum reachable with the first `i` entries to the
+    // fewest of them needed.
+    let mut fewest = vec![HashMap::new()];
+    fewest[0].insert(0, 0);
+    for &entry in entries {
+        let entry = entry as i128;
+        if entry < 0 {
+            below -= entry;
         } else {
-            right += 1;
+            above -= entry;
+        }
+
+        let previous = fewest.last().unwrap();
+        let mut next = HashMap::with_capacity(previous.len());
+        for (&sum, &count) in previous {
+            for (sum, count) in [(sum, count), (sum + entry, count + 1)].iter().copied() {
+                if sum + below <= target && target <= sum + above {
+                    let best = next.entry(sum).or_insert(count);
+                    *best = count.min(*best);
+                }
+            }
+        }
+        fewest.push(next);
+    }
+
+    // Walk back from the target, taking each entry the fewest count needed.
+    let mut count = *fewest.last().unwrap().get(&target)?;
+    let mut sum = target;
+    let mut chosen = Vec::with_capacity(count);
+    for (index, &entry) in entries.iter().enumerate().rev() {
+        if fewest[index].get(&sum) != Some(&count) {
+            chosen.push((entry, index));
+            sum -= entry as i128;
+            count -= 1;
         }
     }
 
-    0
+    chosen.sort_unstable();
+    Some(KSum {
+        entries: chosen.iter().map(|&(entry, _)| entry).collect(),
+        indices: chosen.iter().map(|&(_, index)| index).collect(),
+    })
+}
+
+/// The product of `k` entries summing to `target`.
+fn solve(input: &[i64], k: usize, target: i64) -> Result<i64, SolveError> {
+    let sum = find_k_sum(input, k, target).ok_or(SolveError::NotFound { k, target })?;
+    Ok(sum.product()?)
+}
+
+#[aoc(day1, part1)]
+pub fn solve_part1(input: &[i64]) -> Result<i64, SolveError> {
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &[i64], params: &Params) -> Result<i64, SolveError> {
+    solve(input, 2, params.get("target"))
 }
 
 #[aoc(day1, part2)]
-pub fn solve_part2(input: &[u32]) -> u32 {
-    let (mut left, mut middle, mut right) = (0, 1, 2);
-    while left < input.len() - 2 {
-        let left_val = input[left];
-
-        while middle < input.len() - 1 && right < input.len() {
-            let middle_val = input[middle];
-            let right_val = input[right];
-
-            let sum = left_val + middle_val + right_val;
-            if sum == 2020 {
-                return left_val * middle_val * right_val;
+pub fn solve_part2(input: &[i64]) -> Result<i64, SolveError> {
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &[i64], params: &Params) -> Result<i64, SolveError> {
+    solve(input, 3, params.get("target"))
+}
+
+pub static VARIANTS: &[Variant<Vec<i64>>] = &[
+    Variant {
+        name: "hashing",
+        part: Part::One,
+        solve: |input, params| solve_part1_hashing(input, params.get("target")).into_answer(),
+    },
+    Variant {
+        name: "hashing",
+        part: Part::Two,
+        solve: |input, params| solve_part2_hashing(input, params.get("target")).into_answer(),
+    },
+];
+
+/// Looks each entry's complement up among the entries before it, rather than
+/// relying on the input being sorted.
+fn solve_part1_hashing(input: &[i64], target: i64) -> Result<i64, SolveError> {
+    let mut seen = HashSet::new();
+    for &entry in input {
+        if let Some(complement) = target.checked_sub(entry) {
+            if seen.contains(&complement) {
+                return Ok(product(&[complement, entry])?);
             }
+        }
+        seen.insert(entry);
+    }
 
-            if sum > 2020 {
-                middle += 1;
-                right = middle + 1;
-            } else {
-                right += 1;
+    Err(SolveError::NotFound { k: 2, target })
+}
+
+/// Fixes the first entry and looks for a pair summing to the rest by hashing.
+fn solve_part2_hashing(input: &[i64], target: i64) -> Result<i64, SolveError> {
+    for (i, &first) in input.iter().enumerate() {
+        let rest = match target.checked_sub(first) {
+            Some(rest) => rest,
+            None => continue,
+        };
+        let mut seen = HashSet::new();
+        for &second in &input[i + 1..] {
+            if let Some(third) = rest.checked_sub(second) {
+                if seen.contains(&third) {
+                    return Ok(product(&[first, third, second])?);
+                }
             }
+            seen.insert(second);
         }
+    }
+
+    Err(SolveError::NotFound { k: 3, target })
+}
+
+#[test]
+fn test_hashing() {
+    let input = input_generator("1721\n979\n366\n299\n675\n1456");
+    assert_eq!(solve_part1_hashing(&input, 2020), solve_part1(&input));
+    assert_eq!(solve_part2_hashing(&input, 2020), solve_part2(&input));
+    let not_found = SolveError::NotFound { k: 2, target: 1 };
+    assert_eq!(solve_part1_hashing(&input, 1), Err(not_found.clone()));
+    assert_eq!(solve(&input, 2, 1), Err(not_found));
+    let e = solve_part2_hashing(&input, 1).err().unwrap();
+    assert_eq!(e, solve(&input, 3, 1).err().unwrap());
+    assert_eq!(e.to_string(), "no 3 entries sum to 1");
+}
+
+#[test]
+fn test_find_k_sum() {
+    let input = input_generator("1721\n979\n366\n299\n675\n1456");
+    let sum = find_k_sum(&input, 2, 2020).unwrap();
+    assert_eq!(sum.entries, [299, 1721]);
+    assert_eq!(sum.indices, [3, 0]);
+    let sum = find_k_sum(&input, 3, 2020).unwrap();
+    assert_eq!(sum.entries, [366, 675, 979]);
+    assert_eq!(sum.indices, [2, 4, 1]);
+    assert_eq!(sum.product(), Ok(241861950));
+
+    assert_eq!(find_k_sum(&input, 1, 366).unwrap().indices, [2]);
+    assert_eq!(
+        find_k_sum(&input, 4, 299 + 366 + 675 + 979)
+            .unwrap()
+            .indices,
+        [3, 2, 4, 1]
+    );
+    assert_eq!(find_k_sum(&input, 0, 0).unwrap().entries, []);
+    assert_eq!(find_k_sum(&input, 2, 1), None);
+    assert_eq!(find_k_sum(&input, 7, 2020), None);
+
+    // An entry can't be used twice, but equal entries on different lines can.
+    let input = input_generator("1010\n5\n1010");
+    assert_eq!(find_k_sum(&input, 2, 2020).unwrap().indices, [0, 2]);
+    assert_eq!(find_k_sum(&input[..2], 2, 2020), None);
+}
 
-        left += 1;
-        middle = left + 1;
-        right = middle + 1;
+#[test]
+fn test_k_sums() {
+    let input = input_generator("1010\n5\n1010\n2015\n1010");
+    let sums = k_sums(&input, 2, 2020).collect::<Vec<_>>();
+    assert_eq!(
+        sums.iter()
+            .map(|sum| sum.indices.clone())
+            .collect::<Vec<_>>(),
+        [vec![1, 3], vec![0, 2], vec![0, 4], vec![2, 4]]
+    );
+    assert_eq!(sums[1].entries, [1010, 1010]);
+    assert_eq!(count_k_sums(&input, 2, 2020), Some(4));
+    assert_eq!(sums[0], find_k_sum(&input, 2, 2020).unwrap());
+
+    assert_eq!(k_sums(&input, 3, 3030).count(), 4);
+    assert_eq!(count_k_sums(&input, 3, 3030), Some(4));
+    assert_eq!(k_sums(&input, 6, 0).count(), 0);
+    assert_eq!(k_sums(&input, 0, 0).count(), 1);
+    assert_eq!(count_k_sums(&input, 0, 0), Some(1));
+
+    let input = input_generator("1\n2\n3\n4\n5\n5\n6\n7\n0\n0");
+    for k in 0..6 {
+        for target in 0..30 {
+            let sums = k_sums(&input, k, target).collect::<Vec<_>>();
+            assert_eq!(Some(sums.len() as u64), count_k_sums(&input, k, target));
+            assert!(sums
+                .iter()
+                .all(|sum| sum.entries.iter().sum::<i64>() == target));
+        }
     }
+}
+
+#[test]
+fn test_count_many_k_sums() {
+    // Any 33 of 66 equal entries make a set. That count fits in a u64, but
+    // working out 66 choose 33 goes past one on the way.
+    let input = vec![1010; 66];
+    assert_eq!(count_k_sums(&input, 33, 33330), Some(7219428434016265740));
+    // 70 choose 35 doesn't fit in a u64 at all.
+    let input = vec![1010; 70];
+    assert_eq!(count_k_sums(&input, 35, 35350), None);
+    assert_eq!(count_k_sums(&input, 35, 35351), Some(0));
+}
+
+#[test]
+fn test_signed() {
+    let input = input_generator("-5\n2025\n3001\n-988\n7");
+    assert_eq!(find_k_sum(&input, 2, 2020).unwrap().entries, [-5, 2025]);
+    assert_eq!(solve_part1(&input), Ok(-10125));
+    assert_eq!(solve_part2(&input), Ok(-988 * 7 * 3001));
+    assert_eq!(solve_part1_hashing(&input, 2020), solve_part1(&input));
+    assert_eq!(solve_part2_hashing(&input, 2020), solve_part2(&input));
+    assert_eq!(
+        count_k_sums(&input, 2, 2020),
+        Some(k_sums(&input, 2, 2020).count() as u64)
+    );
 
-    0
+    let input = input_generator(&format!("{}\n{}\n-1", i64::MAX, i64::MIN));
+    assert_eq!(find_k_sum(&input, 2, -1).unwrap().indices, [1, 0]);
+    assert_eq!(find_k_sum(&input, 3, -2).unwrap().indices, [1, 2, 0]);
+    let e = solve(&input, 2, -1).err().unwrap();
+    assert_eq!(e, solve_part1_hashing(&input, -1).err().unwrap());
+    assert_eq!(
+        e.to_string(),
+        format!(
+            "the product {} * {} overflows a 64-bit integer",
+            i64::MIN,
+            i64::MAX
+        )
+    );
+}
+
+#[test]
+fn test_min_subset_sum() {
+    let input = input_generator("1721\n979\n366\n299\n675\n1456");
+    let sum = min_subset_sum(&input, 2020).unwrap();
+    assert_eq!(sum.entries, [299, 1721]);
+    assert_eq!(sum.indices, [3, 0]);
+    assert_eq!(min_subset_sum(&input, 1721).unwrap().indices, [0]);
+    assert_eq!(
+        min_subset_sum(&input, 366 + 675 + 299)
+            .unwrap()
+            .entries
+            .len(),
+        3
+    );
+    assert_eq!(min_subset_sum(&input, 0).unwrap().entries, []);
+    assert_eq!(min_subset_sum(&input, 1), None);
+    assert_eq!(min_subset_sum(&input, input.iter().sum::<i64>() + 1), None);
+
+    let input = input_generator("-7\n12\n3\n3\n-1");
+    assert_eq!(min_subset_sum(&input, 5).unwrap().entries, [-7, 12]);
+    assert_eq!(min_subset_sum(&input, -8).unwrap().indices, [0, 4]);
+    assert_eq!(min_subset_sum(&input, 6).unwrap().indices, [2, 3]);
+
+    // Agrees with trying every size in turn.
+    let mut rng = crate::gen::Rng::new(1);
+    for _ in 0..50 {
+        let input = (0..8)
+            .map(|_| rng.between(0, 40) as i64 - 15)
+            .collect::<Vec<_>>();
+        for target in -30..60 {
+            let smallest = (0..=input.len()).find(|&k| k_sums(&input, k, target).next().is_some());
+            let sum = min_subset_sum(&input, target);
+            assert_eq!(sum.as_ref().map(|sum| sum.entries.len()), smallest);
+            if let Some(sum) = sum {
+                assert_eq!(sum.entries.iter().sum::<i64>(), target);
+                assert!(sum
+                    .indices
+                    .iter()
+                    .zip(&sum.entries)
+                    .all(|(&i, &entry)| input[i] == entry));
+            }
+        }
+    }
 }
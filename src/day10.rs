@@ -1,17 +1,21 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 
 #[aoc_generator(day10)]
-pub fn input_generator(input: &str) -> Vec<usize> {
-    input
-        .lines()
-        .map(|line| line.parse::<usize>().unwrap())
+pub fn try_input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse::lines(10, input)
+        .map(|line| line.parse(line.text(), "an unsigned integer"))
         .collect()
 }
 
+pub fn input_generator(input: &str) -> Vec<usize> {
+    try_input_generator(input).unwrap()
+}
+
 #[aoc(day10, part1)]
 pub fn solve_part1(input: &[usize]) -> usize {
-    let mut input: Vec<_> = input.iter().copied().chain(Some(0).into_iter()).collect();
+    let mut input: Vec<_> = input.iter().copied().chain(Some(0)).collect();
     input.sort();
     input.push(input[input.len() - 1] + 3);
 
@@ -34,7 +38,7 @@ pub fn solve_part1(input: &[usize]) -> usize {
 
 #[aoc(day10, part2)]
 pub fn solve_part2(input: &[usize]) -> u128 {
-    let mut input: Vec<_> = input.iter().copied().chain(Some(0).into_iter()).collect();
+    let mut input: Vec<_> = input.iter().copied().chain(Some(0)).collect();
     input.sort();
     input.push(input[input.len() - 1] + 3);
 
@@ -45,7 +49,7 @@ pub fn solve_part2(input: &[usize]) -> u128 {
 
     for (i, arr) in input.windows(4).enumerate() {
         let last = arr[3];
-        for (j, v) in arr[..3].into_iter().enumerate() {
+        for (j, v) in arr[..3].iter().enumerate() {
             if last - *v < 4 {
                 res[i + 3] += res[i + j];
             }
@@ -54,118 +58,3 @@ pub fn solve_part2(input: &[usize]) -> u128 {
 
     res[input.len() - 1]
 }
-
-#[test]
-fn test_input1() {
-    let content = "16
-10
-15
-5
-1
-11
-7
-19
-6
-12
-4";
-    let input = input_generator(content);
-    let result = solve_part1(&input);
-    assert_eq!(result, 35);
-}
-
-#[test]
-fn test_input1_2() {
-    let content = "28
-33
-18
-42
-31
-14
-46
-20
-48
-47
-24
-23
-49
-45
-19
-38
-39
-11
-1
-32
-25
-35
-8
-17
-7
-9
-4
-2
-34
-10
-3";
-    let input = input_generator(content);
-    let result = solve_part1(&input);
-    assert_eq!(result, 220);
-}
-
-#[test]
-fn test_input2() {
-    // 4 5 6 7 is the tricky one here...
-    // 5 -> 7 => 2 chances, but NOT when
-    // 4 -> 6 or 4 -> 7, since NO 5 ...
-    let content = "16
-10
-15
-5
-1
-11
-7
-19
-6
-12
-4";
-    let input = input_generator(content);
-    let result = solve_part2(&input);
-    assert_eq!(result, 8);
-}
-
-#[test]
-fn test_input2_2() {
-    let content = "28
-33
-18
-42
-31
-14
-46
-20
-48
-47
-24
-23
-49
-45
-19
-38
-39
-11
-1
-32
-25
-35
-8
-17
-7
-9
-4
-2
-34
-10
-3";
-    let input = input_generator(content);
-    let result = solve_part2(&input);
-    assert_eq!(result, 19208);
-}
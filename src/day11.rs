@@ -1,6 +1,8 @@
+use crate::grid::{self, Grid};
+use crate::parse::ParseError;
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 use std::fmt::Debug;
 
 #[derive(Copy, Clone)]
@@ -23,42 +25,47 @@ impl TryFrom<char> for Space {
     }
 }
 
+impl From<Space> for char {
+    fn from(space: Space) -> char {
+        match space {
+            Space::Floor => '.',
+            Space::Empty => 'L',
+            Space::Occupied => '#',
+        }
+    }
+}
+
 struct Life {
-    universe: Vec<Vec<Space>>,
+    universe: Grid<Space>,
 }
 
 impl Life {
-    fn next_state(&mut self) -> bool {
+    /// Applies one round of the seating rules, where `visible` counts the
+    /// occupied seats a seat can see and a seat empties once it sees
+    /// `tolerance` of them. Returns whether anything changed.
+    fn step(
+        &mut self,
+        visible: fn(&Grid<Space>, (usize, usize)) -> usize,
+        tolerance: usize,
+    ) -> bool {
         let mut board = self.universe.clone();
         let mut changed = false;
-        let board_len = board.len();
-        for (y, row) in board.iter_mut().enumerate() {
-            let row_len = row.len();
-            for (x, value) in row.iter_mut().enumerate() {
-                let mut occupied_count = 0;
-
-                for kernel_x in x.saturating_sub(1)..=(x + 1).min(row_len - 1) {
-                    for kernel_y in y.saturating_sub(1)..=(y + 1).min(board_len - 1) {
-                        if kernel_x == x && kernel_y == y {
-                            continue;
-                        }
-                        if matches!(self.universe[kernel_y][kernel_x], Space::Occupied) {
-                            occupied_count += 1;
-                        }
-                    }
-                }
+        for position in self.universe.positions() {
+            let value = &mut board[position];
+            if matches!(value, Space::Floor) {
+                continue;
+            }
 
-                match (value, occupied_count) {
-                    (v @ Space::Empty, 0) => {
-                        *v = Space::Occupied;
-                        changed = true;
-                    }
-                    (v @ Space::Occupied, o) if o > 3 => {
-                        *v = Space::Empty;
-                        changed = true;
-                    }
-                    _ => {} // no-op
+            match (value, visible(&self.universe, position)) {
+                (v @ Space::Empty, 0) => {
+                    *v = Space::Occupied;
+                    changed = true;
                 }
+                (v @ Space::Occupied, o) if o >= tolerance => {
+                    *v = Space::Empty;
+                    changed = true;
+                }
+                _ => {} // no-op
             }
         }
 
@@ -66,120 +73,63 @@ impl Life {
         changed
     }
 
-    fn next_state2(&mut self) -> bool {
-        let mut board = self.universe.clone();
-        let mut changed = false;
-        let board_len = board.len();
-        for (y, row) in board.iter_mut().enumerate() {
-            let row_len = row.len();
-            for (x, value) in row.iter_mut().enumerate() {
-
-                if matches!(value, Space::Floor) {
-                    continue;
-                }
-
-                let mut occupied_count = 0;
-                // mutable version for slopes in this one... should be improved
+    fn next_state(&mut self) -> bool {
+        self.step(
+            |universe, position| {
+                universe
+                    .neighbors(position, &grid::ALL_DIRECTIONS)
+                    .filter(|&seat| matches!(universe[seat], Space::Occupied))
+                    .count()
+            },
+            4,
+        )
+    }
 
-                for (&search_x, &search_y) in [-1, -1, 0, 1, 1, 1, 0, -1]
+    fn next_state2(&mut self) -> bool {
+        // Look past the floor to the first seat in each direction.
+        self.step(
+            |universe, position| {
+                grid::ALL_DIRECTIONS
                     .iter()
-                    .zip([0, 1, 1, 1, 0, -1, -1, -1].iter())
-                {
-                    let mut kernel_x = search_x + x as isize;
-                    let mut kernel_y = search_y + y as isize;
-                    while kernel_x >= 0
-                        && kernel_x < row_len as isize
-                        && kernel_y >= 0
-                        && kernel_y < board_len as isize
-                    {
-                        match self.universe[kernel_y as usize][kernel_x as usize] {
-                            Space::Floor => {
-                                kernel_x += search_x;
-                                kernel_y += search_y;
-                            }
-                            Space::Empty => {
-                                break;
-                            }
-                            Space::Occupied => {
-                                occupied_count += 1;
-                                break;
-                            }
-                        }
-                    }
-                }
-
-                match (value, occupied_count) {
-                    (v @ Space::Empty, 0) => {
-                        *v = Space::Occupied;
-                        changed = true;
-                    }
-                    (v @ Space::Occupied, o) if o > 4 => {
-                        *v = Space::Empty;
-                        changed = true;
-                    }
-                    _ => {} // no-op
-                }
-            }
-        }
-
-        self.universe = board;
-        changed
+                    .filter_map(|&direction| {
+                        universe
+                            .ray(position, direction)
+                            .find(|&seat| !matches!(universe[seat], Space::Floor))
+                    })
+                    .filter(|&seat| matches!(universe[seat], Space::Occupied))
+                    .count()
+            },
+            5,
+        )
     }
 
     fn occupied(&self) -> usize {
         self.universe
             .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|value| {
-                        if matches!(value, Space::Occupied) {
-                            1
-                        } else {
-                            0
-                        }
-                    })
-                    .sum::<usize>()
-            })
-            .sum::<usize>()
+            .filter(|value| matches!(value, Space::Occupied))
+            .count()
     }
 }
 
 impl Debug for Life {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let display = self
-            .universe
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|space| match space {
-                        Space::Floor => '.',
-                        Space::Empty => 'L',
-                        Space::Occupied => '#',
-                    })
-                    .collect::<String>()
-                    + "\n"
-            })
-            .collect::<String>();
-        write!(f, "{}", display)
+        write!(f, "{}", self.universe)
     }
 }
 
 #[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> Vec<Vec<Space>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.try_into().unwrap())
-                .collect::<Vec<_>>()
-        })
-        .collect()
+pub fn try_input_generator(input: &str) -> Result<Grid<Space>, ParseError> {
+    Grid::parse(11, input, "one of `.`, `L` or `#`")
+}
+
+pub fn input_generator(input: &str) -> Grid<Space> {
+    try_input_generator(input).unwrap()
 }
 
 #[aoc(day11, part1)]
-pub fn solve_part1(input: &[Vec<Space>]) -> usize {
+pub fn solve_part1(input: &Grid<Space>) -> usize {
     let mut life = Life {
-        universe: input.iter().cloned().collect(),
+        universe: input.clone(),
     };
 
     while life.next_state() {}
@@ -187,45 +137,11 @@ pub fn solve_part1(input: &[Vec<Space>]) -> usize {
 }
 
 #[aoc(day11, part2)]
-pub fn solve_part2(input: &[Vec<Space>]) -> usize {
+pub fn solve_part2(input: &Grid<Space>) -> usize {
     let mut life = Life {
-        universe: input.iter().cloned().collect(),
+        universe: input.clone(),
     };
 
     while life.next_state2() {}
     life.occupied()
 }
-
-#[test]
-fn test_input1() {
-    let content = "L.LL.LL.LL
-LLLLLLL.LL
-L.L.L..L..
-LLLL.LL.LL
-L.LL.LL.LL
-L.LLLLL.LL
-..L.L.....
-LLLLLLLLLL
-L.LLLLLL.L
-L.LLLLL.LL";
-    let input = input_generator(content);
-    let result = solve_part1(&input);
-    assert_eq!(result, 37);
-}
-
-#[test]
-fn test_input2() {
-    let content = "L.LL.LL.LL
-LLLLLLL.LL
-L.L.L..L..
-LLLL.LL.LL
-L.LL.LL.LL
-L.LLLLL.LL
-..L.L.....
-LLLLLLLLLL
-L.LLLLLL.L
-L.LLLLL.LL";
-    let input = input_generator(content);
-    let result = solve_part2(&input);
-    assert_eq!(result, 26);
-}
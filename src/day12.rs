@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 
@@ -19,31 +20,32 @@ pub struct Move {
 }
 
 #[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> Vec<Move> {
-    input
-        .lines()
+pub fn try_input_generator(input: &str) -> Result<Vec<Move>, ParseError> {
+    parse::lines(12, input)
         .map(|line| {
-            let mut chars = line.chars();
-            Move {
-                dir: match chars.next() {
-                    Some('N') => Direction::North,
-                    Some('E') => Direction::East,
-                    Some('S') => Direction::South,
-                    Some('W') => Direction::West,
-                    Some('L') => Direction::Left,
-                    Some('R') => Direction::Right,
-                    Some('F') => Direction::Forward,
-                    i => panic!("Unexpected input: {:?}", i),
-                },
-                amount: chars
-                    .collect::<String>()
-                    .parse()
-                    .expect("Expected string to end in a number"),
-            }
+            let mut chars = line.text().chars();
+            let dir = match chars.next() {
+                Some('N') => Direction::North,
+                Some('E') => Direction::East,
+                Some('S') => Direction::South,
+                Some('W') => Direction::West,
+                Some('L') => Direction::Left,
+                Some('R') => Direction::Right,
+                Some('F') => Direction::Forward,
+                _ => return Err(line.char_error(0, "one of `N`, `E`, `S`, `W`, `L`, `R` or `F`")),
+            };
+            Ok(Move {
+                dir,
+                amount: line.parse(chars.as_str(), "an amount")?,
+            })
         })
         .collect()
 }
 
+pub fn input_generator(input: &str) -> Vec<Move> {
+    try_input_generator(input).unwrap()
+}
+
 #[aoc(day12, part1)]
 pub fn solve_part1(input: &[Move]) -> usize {
     let forward_options = [(1, 0), (0, -1), (-1, 0), (0, 1)];
@@ -88,12 +90,12 @@ pub fn solve_part2(input: &[Move]) -> usize {
             // Fun fact: Bastardized 2D rotation matrix
             Direction::Left => {
                 for _ in 0..r#move.amount / 90 {
-                    waypoint = (-1 * waypoint.1, waypoint.0);
+                    waypoint = (-waypoint.1, waypoint.0);
                 }
             }
             Direction::Right => {
                 for _ in 0..r#move.amount / 90 {
-                    waypoint = (waypoint.1, -1 * waypoint.0);
+                    waypoint = (waypoint.1, -waypoint.0);
                 }
             }
             Direction::Forward => {
@@ -1,3 +1,5 @@
+use crate::parse::{self, Line, ParseError};
+use crate::runner::{Part, Solution, Variant};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use std::num::NonZeroU32;
@@ -8,23 +10,35 @@ pub struct Schedule {
 }
 
 #[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> Schedule {
-    let mut lines = input.lines();
-    Schedule {
-        earliest: lines.next().unwrap().parse().unwrap(),
-        bus_ids: lines
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|s| {
-                if s == "x" {
-                    None
-                } else {
-                    s.parse::<NonZeroU32>().ok()
-                }
-            })
-            .collect(),
+pub fn try_input_generator(input: &str) -> Result<Schedule, ParseError> {
+    let mut lines = parse::lines(13, input);
+    let first = lines
+        .next()
+        .ok_or_else(|| Line::new(13, 0, input).missing("an earliest departure time"))?;
+    let earliest = first.parse(first.text(), "an earliest departure time")?;
+    let second = lines
+        .next()
+        .ok_or_else(|| first.missing("a second line of bus ids"))?;
+    let bus_ids = second
+        .text()
+        .split(',')
+        .map(|s| {
+            if s == "x" {
+                Ok(None)
+            } else {
+                second.parse(s, "a non-zero bus id or `x`").map(Some)
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    if let Some(extra) = lines.next() {
+        return Err(extra.error(extra.text(), "end of input"));
     }
+
+    Ok(Schedule { earliest, bus_ids })
+}
+
+pub fn input_generator(input: &str) -> Schedule {
+    try_input_generator(input).unwrap()
 }
 
 #[aoc(day13, part1)]
@@ -45,7 +59,7 @@ pub fn solve_part1(input: &Schedule) -> usize {
 
 /// Find minimum x that satisfies x = p⁻¹ (mod q). This function does not
 /// guarantee a unique result if `p` and `q` are not coprime.
-/// ```rust,no_run
+/// ```ignore
 /// assert_eq!(5 * modulo_inverse(5, 7) % 7, 1);
 /// ```
 fn modulo_inverse(p: u128, q: u128) -> u128 {
@@ -75,13 +89,14 @@ pub fn solve_part2(input: &Schedule) -> u128 {
     // where M = Πⁿᵢ₌₁mᵢ
     //       bᵢ = M / mᵢ
     //       bᵢ' = bᵢ⁻¹ (mod mᵢ)
-    let big_m: u128 = pos_and_ids.iter().map(|&(_, id)| id as u128).product();
+    let big_m: u128 = pos_and_ids.iter().map(|&(_, id)| id).product();
     let sum: u128 = pos_and_ids
         .iter()
         .map(|&(pos, m)| {
             // Here we substract the `pos` from `m` to get `a` because if we just used
             // the original `pos`, we would get the inverse order for some time t.
-            let a = m - pos;
+            // `pos` can be larger than `m`, so reduce it first.
+            let a = (m - pos % m) % m;
             let b = big_m / m;
             let b_prime = modulo_inverse(b, m);
             assert_eq!(b * b_prime % m, 1);
@@ -91,6 +106,41 @@ pub fn solve_part2(input: &Schedule) -> u128 {
     sum % big_m
 }
 
+pub static VARIANTS: &[Variant<Schedule>] = &[Variant {
+    name: "sieve",
+    part: Part::Two,
+    solve: |input, _| solve_part2_sieve(input).into_answer(),
+}];
+
+/// Finds the timestamp one bus at a time: once it lines up with the buses so
+/// far, stepping by the product of their ids keeps them lined up.
+fn solve_part2_sieve(input: &Schedule) -> u128 {
+    let (mut time, mut step) = (0, 1);
+    for (pos, id) in input.bus_ids.iter().enumerate() {
+        if let Some(id) = id {
+            let id = id.get() as u128;
+            while !(time + pos as u128).is_multiple_of(id) {
+                time += step;
+            }
+            step *= id;
+        }
+    }
+
+    time
+}
+
+#[test]
+fn test_sieve() {
+    for content in &[
+        "0\n7,13,x,x,59,x,31,19",
+        "0\n17,x,13,19",
+        "0\n1789,37,47,1889",
+    ] {
+        let input = input_generator(content);
+        assert_eq!(solve_part2_sieve(&input), solve_part2(&input));
+    }
+}
+
 #[test]
 fn test_input1() {
     let content = "939
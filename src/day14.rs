@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use regex::Regex;
@@ -6,8 +7,7 @@ use std::collections::HashMap;
 pub struct Mask {
     ones_mask: u64,
     zeros_mask: u64,
-    floating_ones: Vec<u64>,
-    floating_zeros: Vec<u64>,
+    floating_mask: u64,
 }
 
 pub struct Set {
@@ -23,8 +23,7 @@ pub enum Op {
 struct Emulator<'a> {
     ones_mask: u64,
     zeros_mask: u64,
-    floating_ones: &'a[u64],
-    floating_zeros: &'a [u64],
+    floating_mask: u64,
     pc: usize,
     instructions: &'a [Op],
     memory: HashMap<u64, u64>,
@@ -40,10 +39,9 @@ impl<'a> Emulator<'a> {
         Self {
             ones_mask: 0,
             zeros_mask: !0 << SHIFT_CORRECTION >> SHIFT_CORRECTION,
-            floating_ones: &[],
-            floating_zeros: &[],
+            floating_mask: 0,
             pc: 0,
-            instructions: &instructions,
+            instructions,
             memory: HashMap::new(),
         }
     }
@@ -57,13 +55,11 @@ impl<'a> Emulator<'a> {
             Op::Mask(Mask {
                 ones_mask,
                 zeros_mask,
-                floating_ones,
-                floating_zeros,
+                floating_mask,
             }) => {
                 self.ones_mask = *ones_mask;
                 self.zeros_mask = *zeros_mask;
-                self.floating_ones = &floating_ones;
-                self.floating_zeros = &floating_zeros;
+                self.floating_mask = *floating_mask;
             }
             Op::Set(Set { address, value }) => {
                 match op_mode {
@@ -72,11 +68,16 @@ impl<'a> Emulator<'a> {
                             .insert(*address, value & self.zeros_mask | self.ones_mask);
                     }
                     OperatingMode::MemoryMask => {
-                        for (&floating_one, &floating_zero) in
-                            self.floating_ones.iter().zip(self.floating_zeros.iter())
-                        {
-                            self.memory
-                                .insert(address & floating_zero | floating_one, *value);
+                        // Walk every subset of the floating bits, starting from the full set
+                        // and counting down to the empty set.
+                        let base = (address | self.ones_mask) & !self.floating_mask;
+                        let mut floating = self.floating_mask;
+                        loop {
+                            self.memory.insert(base | floating, *value);
+                            if floating == 0 {
+                                break;
+                            }
+                            floating = (floating - 1) & self.floating_mask;
                         }
                     }
                 };
@@ -95,67 +96,59 @@ const HIGH_BIT: u64 = 35;
 const SHIFT_CORRECTION: u64 = 64 - 36;
 
 #[aoc_generator(day14)]
-pub fn input_generator(input: &str) -> Vec<Op> {
+pub fn try_input_generator(input: &str) -> Result<Vec<Op>, ParseError> {
     let re = Regex::new(r"^mem\[([0-9]+)\] = ([0-9]+)$").unwrap();
-    input
-        .lines()
+    parse::lines(14, input)
         .map(|line| {
-            if line.starts_with("mask") {
-                let mask = line.split(' ').skip(2).next().unwrap();
-                let (zeros_mask, ones_mask, floating_zeros, floating_ones) =
-                    mask.chars().enumerate().fold(
-                        (0, 0, vec![0], vec![0]),
-                        |(zeros, ones, floating_zeros, floating_ones), (i, c)| {
-                            let val = 1 << (HIGH_BIT - i as u64);
-                            let neg_val = !val << SHIFT_CORRECTION >> SHIFT_CORRECTION;
-                            match c {
-                                '1' => (
-                                    val | zeros,
-                                    val | ones,
-                                    floating_zeros.into_iter().map(|v| val | v).collect(),
-                                    floating_ones.into_iter().map(|v| val | v).collect(),
-                                ),
-                                '0' => (
-                                    neg_val & zeros,
-                                    neg_val & ones,
-                                    floating_zeros.into_iter().map(|v| val | v).collect(),
-                                    floating_ones.into_iter().map(|v| neg_val & v).collect(),
-                                ),
-                                _ => (
-                                    val | zeros,
-                                    neg_val & ones,
-                                    floating_zeros
-                                        .into_iter()
-                                        .flat_map(|v| vec![val | v, neg_val & v])
-                                        .collect(),
-                                    floating_ones
-                                        .into_iter()
-                                        .flat_map(|v| vec![val | v, neg_val & v])
-                                        .collect(),
-                                ),
-                            }
-                        },
-                    );
+            if let Some(mask) = line.text().strip_prefix("mask = ") {
+                if mask.chars().count() != 36 {
+                    return Err(line.error(mask, "a 36 bit mask"));
+                }
+
+                let mut zeros_mask = 0;
+                let mut ones_mask = 0;
+                let mut floating_mask = 0;
+                for (i, c) in mask.char_indices() {
+                    let val = 1 << (HIGH_BIT - i as u64);
+                    match c {
+                        '1' => {
+                            zeros_mask |= val;
+                            ones_mask |= val;
+                        }
+                        '0' => {}
+                        'X' => {
+                            zeros_mask |= val;
+                            floating_mask |= val;
+                        }
+                        _ => {
+                            let bit = &mask[i..i + c.len_utf8()];
+                            return Err(line.error(bit, "one of `0`, `1` or `X`"));
+                        }
+                    }
+                }
 
-                Op::Mask(Mask {
-                    zeros_mask,
+                Ok(Op::Mask(Mask {
                     ones_mask,
-                    floating_ones,
-                    floating_zeros,
-                })
+                    zeros_mask,
+                    floating_mask,
+                }))
             } else {
-                let captures = re
-                    .captures(line)
-                    .expect(&format!("Could not process `{}`", line));
-                Op::Set(Set {
-                    address: captures[1].parse::<u64>().unwrap(),
-                    value: captures[2].parse::<u64>().unwrap(),
-                })
+                let captures = re.captures(line.text()).ok_or_else(|| {
+                    line.error(line.text(), "`mask = <bits>` or `mem[<address>] = <value>`")
+                })?;
+                Ok(Op::Set(Set {
+                    address: line.parse(captures.get(1).unwrap().as_str(), "an address")?,
+                    value: line.parse(captures.get(2).unwrap().as_str(), "a value")?,
+                }))
             }
         })
         .collect()
 }
 
+pub fn input_generator(input: &str) -> Vec<Op> {
+    try_input_generator(input).unwrap()
+}
+
 #[aoc(day14, part1)]
 pub fn solve_part1(input: &[Op]) -> usize {
     let mut emulator = Emulator::new(input);
@@ -1,3 +1,6 @@
+use crate::params::{Param, Params};
+use crate::parse::{self, Line, ParseError};
+use crate::runner::{Part, Solution, Variant};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use std::collections::HashMap;
@@ -35,36 +38,119 @@ impl Generator {
     }
 }
 
+pub const PARAMS: &[Param] = &[
+    Param::at_least::<1>(
+        "part1_generations",
+        "2020",
+        "the turn whose number part 1 reports (at least 1)",
+    ),
+    Param::at_least::<1>(
+        "part2_generations",
+        "30000000",
+        "the turn whose number part 2 reports (at least 1)",
+    ),
+];
+
 #[aoc_generator(day15)]
-pub fn input_generator(input: &str) -> Vec<usize> {
-    input
+pub fn try_input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
+    let mut lines = parse::lines(15, input);
+    let line = lines
+        .next()
+        .ok_or_else(|| Line::new(15, 0, input).missing("a starting number"))?;
+    if let Some(extra) = lines.next() {
+        return Err(extra.error(extra.text(), "a single line of starting numbers"));
+    }
+
+    line.text()
         .split(',')
-        .map(|line| line.parse::<usize>().unwrap())
+        .map(|number| line.parse(number, "an unsigned integer"))
         .collect()
 }
 
-#[aoc(day15, part1)]
-pub fn solve_part1(input: &[usize]) -> usize {
-    const GENERATIONS: usize = 2020;
+pub fn input_generator(input: &str) -> Vec<usize> {
+    try_input_generator(input).unwrap()
+}
+
+/// The number spoken on turn `generations`.
+fn play(input: &[usize], generations: usize) -> usize {
+    if generations <= input.len() {
+        return input[generations - 1];
+    }
+
     let mut generator = Generator::new(input);
     let mut res = 0;
-    for _ in 0..(GENERATIONS - input.len()) {
+    for _ in 0..(generations - input.len()) {
         res = generator.next();
     }
 
     res
 }
 
+#[aoc(day15, part1)]
+pub fn solve_part1(input: &[usize]) -> usize {
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &[usize], params: &Params) -> usize {
+    play(input, params.get("part1_generations"))
+}
+
 #[aoc(day15, part2)]
 pub fn solve_part2(input: &[usize]) -> usize {
-    const GENERATIONS: usize = 30000000;
-    let mut generator = Generator::new(input);
-    let mut res = 0;
-    for _ in 0..(GENERATIONS - input.len()) {
-        res = generator.next();
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &[usize], params: &Params) -> usize {
+    play(input, params.get("part2_generations"))
+}
+
+pub static VARIANTS: &[Variant<Vec<usize>>] = &[
+    Variant {
+        name: "dense",
+        part: Part::One,
+        solve: |input, params| play_dense(input, params.get("part1_generations")).into_answer(),
+    },
+    Variant {
+        name: "dense",
+        part: Part::Two,
+        solve: |input, params| play_dense(input, params.get("part2_generations")).into_answer(),
+    },
+];
+
+/// Like [`play`], but remembers when each number was last spoken in a table
+/// indexed by the number. No number can exceed the turn count, so the table
+/// never grows.
+fn play_dense(input: &[usize], generations: usize) -> usize {
+    if generations <= input.len() {
+        return input[generations - 1];
     }
 
-    res
+    const UNSEEN: u32 = u32::MAX;
+    let largest = input.iter().copied().max().unwrap();
+    let mut last_seen = vec![UNSEEN; generations.max(largest + 1)];
+    for (turn, &number) in input[..input.len() - 1].iter().enumerate() {
+        last_seen[number] = turn as u32;
+    }
+
+    let mut last = input[input.len() - 1];
+    for turn in input.len() - 1..generations - 1 {
+        let next = match last_seen[last] {
+            UNSEEN => 0,
+            seen => turn - seen as usize,
+        };
+        last_seen[last] = turn as u32;
+        last = next;
+    }
+
+    last
+}
+
+#[test]
+fn test_dense() {
+    for &(content, generations) in &[("0,3,6", 2020), ("3,1,2", 2020), ("0,3,6", 4), ("0,3,6", 2)] {
+        let input = input_generator(content);
+        assert_eq!(play_dense(&input, generations), play(&input, generations));
+    }
 }
 
 #[test]
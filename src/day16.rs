@@ -1,3 +1,5 @@
+use crate::params::{Param, Params};
+use crate::parse::{self, Line, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use std::collections::HashMap;
@@ -53,52 +55,94 @@ pub struct Input {
     nearby_tickets: Vec<Ticket>,
 }
 
+pub const PARAMS: &[Param] = &[Param::new::<String>(
+    "prefix",
+    "departure",
+    "the prefix of the fields whose values part 2 multiplies",
+)];
+
 #[aoc_generator(day16)]
-pub fn input_generator(input: &str) -> Input {
-    fn parse_ticket(line: &str) -> Ticket {
-        Ticket(
-            line.split(',')
-                .map(|chunk| chunk.parse::<usize>().unwrap())
-                .collect(),
-        )
+pub fn try_input_generator(input: &str) -> Result<Input, ParseError> {
+    fn parse_ticket(line: Line<'_>) -> Result<Ticket, ParseError> {
+        Ok(Ticket(
+            line.text()
+                .split(',')
+                .map(|chunk| line.parse(chunk, "a ticket value"))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
+    fn parse_range(line: &Line<'_>, chunk: &str) -> Result<RangeInclusive<usize>, ParseError> {
+        let mut parts = chunk.split('-');
+        let start = line.parse_next(parts.next(), "a range start")?;
+        let end = line.parse_next(parts.next(), "a range end")?;
+        if let Some(extra) = parts.next() {
+            return Err(line.error(extra, "a single `-` per range"));
+        }
+
+        Ok(start..=end)
+    }
+
+    fn expect_line<'a>(
+        lines: &mut impl Iterator<Item = Line<'a>>,
+        previous: &Line<'a>,
+        text: &str,
+    ) -> Result<Line<'a>, ParseError> {
+        let expected = format!("`{}`", text);
+        match lines.next() {
+            Some(line) if line.text() == text => Ok(line),
+            Some(line) => Err(line.error(line.text(), expected)),
+            None => Err(previous.missing(expected)),
+        }
     }
 
-    let mut lines = input.lines();
+    let mut lines = parse::lines(16, input);
     let mut rules = vec![];
-    while let Some(line) = lines.next() {
-        if line.is_empty() {
+    let mut previous = Line::new(16, 0, input);
+    for line in &mut lines {
+        previous = line;
+        if line.text().is_empty() {
             break;
         }
 
         rules.push({
-            let mut split = line.split(": ");
+            let mut split = line.text().split(": ");
             let name = String::from(split.next().unwrap());
-            let mut ranges = split.next().unwrap().split(" or ").map(|chunk| {
-                let mut parts = chunk.split('-').map(|i| i.parse::<usize>().unwrap());
-                parts.next().unwrap()..=parts.next().unwrap()
-            });
-            let mut rule = Rule::new(name, ranges.next().unwrap());
+            let ranges = split.next().ok_or_else(|| line.missing("`: <ranges>`"))?;
+            let mut ranges = ranges.split(" or ").map(|chunk| parse_range(&line, chunk));
+            let mut rule = Rule::new(name, ranges.next().unwrap()?);
             for range in ranges {
-                rule.add(range);
+                rule.add(range?);
             }
 
             rule
         });
     }
-    assert_eq!(lines.next().unwrap(), "your ticket:");
-    let your_ticket = parse_ticket(lines.next().unwrap());
-    assert_eq!(lines.next().unwrap(), "");
-    assert_eq!(lines.next().unwrap(), "nearby tickets:");
-    let nearby_tickets = lines.map(parse_ticket).collect();
-    Input {
+    let header = expect_line(&mut lines, &previous, "your ticket:")?;
+    let ticket_line = lines
+        .next()
+        .ok_or_else(|| header.missing("your ticket values"))?;
+    let your_ticket = parse_ticket(ticket_line)?;
+    let blank = expect_line(&mut lines, &ticket_line, "")?;
+    expect_line(&mut lines, &blank, "nearby tickets:")?;
+    let nearby_tickets = lines.map(parse_ticket).collect::<Result<_, _>>()?;
+    Ok(Input {
         rules,
         your_ticket,
         nearby_tickets,
-    }
+    })
+}
+
+pub fn input_generator(input: &str) -> Input {
+    try_input_generator(input).unwrap()
 }
 
 #[aoc(day16, part1)]
 pub fn solve_part1(input: &Input) -> usize {
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &Input, _params: &Params) -> usize {
     input
         .nearby_tickets
         .iter()
@@ -186,10 +230,15 @@ fn part2_details(input: &Input) -> HashMap<&str, usize> {
 
 #[aoc(day16, part2)]
 pub fn solve_part2(input: &Input) -> usize {
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &Input, params: &Params) -> usize {
+    let prefix = params.get::<String>("prefix");
     let columns = part2_details(input);
     columns
         .into_iter()
-        .filter(|(key, _)| key.starts_with("departure"))
+        .filter(|(key, _)| key.starts_with(&prefix))
         .map(|(_, i)| input.your_ticket.0[i])
         .product()
 }
@@ -1,3 +1,5 @@
+use crate::params::{Param, Params};
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use std::collections::{HashMap, HashSet};
@@ -128,14 +130,14 @@ impl<'a> Iterator for Neighbors4D<'a> {
 fn print_set(set: &HashSet<Point>) {
     let (min_x, min_y, min_z, min_w, max_x, max_y, max_z, max_w) = set.iter().fold(
         (
-            std::isize::MAX,
-            std::isize::MAX,
-            std::isize::MAX,
-            std::isize::MAX,
-            std::isize::MIN,
-            std::isize::MIN,
-            std::isize::MIN,
-            std::isize::MIN,
+            isize::MAX,
+            isize::MAX,
+            isize::MAX,
+            isize::MAX,
+            isize::MIN,
+            isize::MIN,
+            isize::MIN,
+            isize::MIN,
         ),
         |acc, p| {
             (
@@ -165,38 +167,48 @@ fn print_set(set: &HashSet<Point>) {
                         }
                     );
                 }
-                println!("");
+                println!();
             }
-            println!("");
+            println!();
         }
     }
 }
 
 #[aoc_generator(day17)]
-pub fn input_generator(input: &str) -> Input {
-    Input {
-        live_locations: input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars().enumerate().map(move |(x, c)| {
-                    if c == '#' {
-                        Some(Point(x as isize, y as isize, 0, 0))
-                    } else {
-                        None
-                    }
-                })
-            })
-            .filter_map(|c| c)
-            .collect(),
+pub fn try_input_generator(input: &str) -> Result<Input, ParseError> {
+    let mut live_locations = vec![];
+    for (y, line) in parse::lines(17, input).enumerate() {
+        for (x, (i, c)) in line.text().char_indices().enumerate() {
+            match c {
+                '#' => live_locations.push(Point(x as isize, y as isize, 0, 0)),
+                '.' => {}
+                _ => return Err(line.char_error(i, "`#` or `.`")),
+            }
+        }
     }
+
+    Ok(Input { live_locations })
 }
 
+pub fn input_generator(input: &str) -> Input {
+    try_input_generator(input).unwrap()
+}
+
+pub const PARAMS: &[Param] = &[Param::at_least::<1>(
+    "cycles",
+    "6",
+    "how many cycles to simulate (at least 1)",
+)];
+
 #[aoc(day17, part1)]
 pub fn solve_part1(input: &Input) -> usize {
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &Input, params: &Params) -> usize {
     let mut set = input.live_locations.iter().cloned().collect::<HashSet<_>>();
 
-    for _ in 0..6 {
+    for _ in 0..params.get::<usize>("cycles") {
         let mut map = HashMap::new();
         for point in set.iter() {
             for neighbor in point.neighbors_3d() {
@@ -221,9 +233,13 @@ pub fn solve_part1(input: &Input) -> usize {
 
 #[aoc(day17, part2)]
 pub fn solve_part2(input: &Input) -> usize {
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &Input, params: &Params) -> usize {
     let mut set = input.live_locations.iter().cloned().collect::<HashSet<_>>();
 
-    for _ in 0..6 {
+    for _ in 0..params.get::<usize>("cycles") {
         let mut map = HashMap::new();
         for point in set.iter() {
             for neighbor in point.neighbors_4d() {
@@ -1,3 +1,4 @@
+use crate::parse::{self, Line, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use std::collections::HashMap;
@@ -15,48 +16,65 @@ pub enum Op {
     Mul,
 }
 
-fn input_generator_helper(input: &str) -> (Vec<Token>, usize) {
+fn input_generator_helper(
+    line: &Line<'_>,
+    input: &str,
+    open: Option<&str>,
+) -> Result<(Vec<Token>, usize), ParseError> {
     let mut idx = 0;
     let mut tokens = vec![];
     let map = input.char_indices().collect::<HashMap<_, _>>();
 
-    while idx < input.bytes().len() {
-        let c = map.get(&idx).unwrap();
+    while idx < input.len() {
+        let c = map[&idx];
         let mut new_idx = idx + c.len_utf8();
         let token = match c {
             '0'..='9' => Token::Num(c.to_digit(10).unwrap() as usize),
             '+' => Token::Op(Op::Add),
             '*' => Token::Op(Op::Mul),
             '(' => {
-                let (tokens, tokens_read) = input_generator_helper(input.get(new_idx..).unwrap());
+                let (tokens, tokens_read) =
+                    input_generator_helper(line, &input[new_idx..], Some(&input[idx..new_idx]))?;
                 new_idx += tokens_read;
                 Token::Paren(tokens)
             }
-            ')' => return (tokens, new_idx),
-            _ => {
+            ')' if open.is_some() => return Ok((tokens, new_idx)),
+            ')' => return Err(line.error(&input[idx..new_idx], "a `(` before this `)`")),
+            ' ' => {
                 idx = new_idx;
                 continue;
             }
+            _ => {
+                let token = &input[idx..new_idx];
+                return Err(line.error(token, "a digit, `+`, `*`, `(` or `)`"));
+            }
         };
         tokens.push(token);
         idx = new_idx;
     }
 
-    (tokens, input.len())
+    if let Some(open) = open {
+        return Err(line.error(open, "a matching `)`"));
+    }
+
+    Ok((tokens, input.len()))
 }
 
 #[aoc_generator(day18)]
-pub fn input_generator(input: &str) -> Vec<Vec<Token>> {
-    input
-        .lines()
+pub fn try_input_generator(input: &str) -> Result<Vec<Vec<Token>>, ParseError> {
+    parse::lines(18, input)
         .map(|line| {
-            let (result, remainder) = input_generator_helper(line);
-            assert_eq!(remainder, line.len());
-            result
+            let (result, remainder) = input_generator_helper(&line, line.text(), None)?;
+            assert_eq!(remainder, line.text().len());
+            Ok(result)
         })
         .collect()
 }
 
+pub fn input_generator(input: &str) -> Vec<Vec<Token>> {
+    try_input_generator(input).unwrap()
+}
+
 fn update_result(op_stack: &mut Vec<Op>, left: usize, right: usize) -> usize {
     let op = op_stack
         .pop()
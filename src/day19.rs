@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use std::collections::HashMap;
@@ -14,7 +15,7 @@ pub struct Graph {
 impl Graph {
     fn matches_root(&self, input: &str) -> bool {
         #[allow(dead_code)]
-        fn print_bools(bools: &Vec<Vec<HashMap<usize, bool>>>) {
+        fn print_bools(bools: &[Vec<HashMap<usize, bool>>]) {
             for v in bools.iter().rev() {
                 for v in v.iter() {
                     if let Some(i) = v.iter().find_map(|(&i, &b)| if b { Some(i) } else { None }) {
@@ -23,7 +24,7 @@ impl Graph {
                         print!(" .|");
                     }
                 }
-                println!("");
+                println!();
             }
         }
 
@@ -64,15 +65,15 @@ impl Graph {
                                 }
                             })
                             .collect::<Vec<_>>();
-                        if sequence.len() > 0 {
+                        if !sequence.is_empty() {
                             Some((i, sequence))
                         } else {
                             None
                         }
                     }) {
                         if choice.iter().any(|&[b, c]| {
-                            let res = bools[l - 1][m - 2].get(&b).map(|b| *b).unwrap_or(false)
-                                && bools[m - 1][r - 1].get(&c).map(|b| *b).unwrap_or(false);
+                            let res = bools[l - 1][m - 2].get(&b).copied().unwrap_or(false)
+                                && bools[m - 1][r - 1].get(&c).copied().unwrap_or(false);
                             // println!(
                             //     "[{}][{}][{}] && [{}][{}][{}] = {}",
                             //     (l - 1),
@@ -92,7 +93,7 @@ impl Graph {
             }
         }
 
-        bools[0][n - 1].get(&0).map(|b| *b).unwrap_or(false)
+        bools[0][n - 1].get(&0).copied().unwrap_or(false)
     }
 }
 
@@ -117,47 +118,53 @@ struct CfgNode {
 }
 
 #[aoc_generator(day19)]
-pub fn input_generator(input: &str) -> Input {
-    let mut lines = input.lines();
-    let mut next_line = lines.next();
+pub fn try_input_generator(input: &str) -> Result<Input, ParseError> {
+    let mut lines = parse::lines(19, input);
     let mut nodes = HashMap::new();
 
-    while next_line.map(|l| l.len() != 0).unwrap_or(false) {
-        let line = next_line.unwrap();
-        let mut parts = line.split(": ");
-        let index = parts.next().unwrap().parse::<usize>().unwrap();
-        let rule = parts.next().unwrap();
+    for line in &mut lines {
+        if line.text().is_empty() {
+            break;
+        }
+
+        let mut parts = line.text().split(": ");
+        let index = line.parse(parts.next().unwrap(), "a rule number")?;
+        let rule = parts.next().ok_or_else(|| line.missing("`: <rule>`"))?;
         let node = if rule.starts_with('"') {
-            CfgNode {
-                choices: vec![CfgInnerNode::Char(rule.chars().skip(1).next().unwrap())],
+            let mut chars = rule.chars();
+            match (chars.next(), chars.next(), chars.next(), chars.next()) {
+                (Some('"'), Some(c), Some('"'), None) => CfgNode {
+                    choices: vec![CfgInnerNode::Char(c)],
+                },
+                _ => return Err(line.error(rule, "a quoted single character")),
             }
         } else {
             CfgNode {
                 choices: rule
                     .split(" | ")
-                    .into_iter()
                     .map(|rule| {
-                        CfgInnerNode::Sequence(
+                        Ok(CfgInnerNode::Sequence(
                             rule.split(' ')
-                                .into_iter()
-                                .map(|idx| idx.parse::<usize>().unwrap())
-                                .collect::<Vec<_>>(),
-                        )
+                                .map(|idx| line.parse(idx, "a rule number"))
+                                .collect::<Result<Vec<_>, _>>()?,
+                        ))
                     })
-                    .collect(),
+                    .collect::<Result<_, _>>()?,
             }
         };
         nodes.insert(index, node);
-
-        next_line = lines.next();
     }
 
-    let input_list = lines.into_iter().map(String::from).collect();
+    let input_list = lines.map(|line| String::from(line.text())).collect();
 
-    Input {
+    Ok(Input {
         rules_graph: nodes,
         input_list,
-    }
+    })
+}
+
+pub fn input_generator(input: &str) -> Input {
+    try_input_generator(input).unwrap()
 }
 
 #[aoc(day19, part1)]
@@ -188,7 +195,7 @@ pub fn solve_part1(input: &Input) -> usize {
             }
         }
 
-        nodes.extend(to_push.into_iter())
+        nodes.extend(to_push)
     }
 
     for i in nodes.keys().cloned().collect::<Vec<_>>() {
@@ -213,7 +220,7 @@ pub fn solve_part1(input: &Input) -> usize {
                 true
             }
         });
-        nodes.get_mut(&i).unwrap().choices.extend(map.into_iter());
+        nodes.get_mut(&i).unwrap().choices.extend(map);
     }
 
     let graph = Graph {
@@ -292,7 +299,7 @@ pub fn solve_part2(input: &Input) -> usize {
             }
         }
 
-        nodes.extend(to_push.into_iter())
+        nodes.extend(to_push)
     }
 
     for i in nodes.keys().cloned().collect::<Vec<_>>() {
@@ -317,7 +324,7 @@ pub fn solve_part2(input: &Input) -> usize {
                 true
             }
         });
-        nodes.get_mut(&i).unwrap().choices.extend(map.into_iter());
+        nodes.get_mut(&i).unwrap().choices.extend(map);
     }
 
     let graph = Graph {
@@ -419,5 +426,5 @@ babaaabbbaaabaababbaabababaaab
 aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#;
     let input = input_generator(content);
     let result = solve_part2(&input);
-    assert_eq!(result, 8);
+    assert_eq!(result, 12);
 }
@@ -1,54 +1,728 @@
+use crate::gen::Rng;
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
 
 pub struct PasswordPolicy {
     lower: usize,
     upper: usize,
-    letter: char,
+    /// A single letter or a longer substring.
+    pattern: String,
+}
+
+impl PasswordPolicy {
+    /// How the pattern reads in a verdict's explanation.
+    fn subject(&self) -> String {
+        let kind = if self.pattern.len() == 1 {
+            "letter"
+        } else {
+            "substring"
+        };
+        format!("{} '{}'", kind, self.pattern)
+    }
+}
+
+impl Display for PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} {}", self.lower, self.upper, self.pattern)
+    }
+}
+
+/// Whether some occurrences of `pattern` could overlap, like `aa` in `aaa`,
+/// which leaves open whether both of them count.
+fn overlaps_itself(pattern: &str) -> bool {
+    (1..pattern.len()).any(|k| pattern[..k] == pattern[pattern.len() - k..])
 }
 
 #[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Vec<(PasswordPolicy, String)> {
+pub fn try_input_generator(input: &str) -> Result<Vec<(PasswordPolicy, String)>, ParseError> {
     let re = Regex::new(r"^(\d+)-(\d+) ([a-z]+): ([a-z]+)$").unwrap();
-    input
-        .lines()
+    parse::lines(2, input)
         .map(|l| {
-            let captures = re.captures_iter(l).next().unwrap();
-            (
+            let captures = re
+                .captures(l.text())
+                .ok_or_else(|| l.error(l.text(), "`<lower>-<upper> <pattern>: <password>`"))?;
+            let lower = captures.get(1).unwrap().as_str();
+            let upper = captures.get(2).unwrap().as_str();
+            let pattern = captures.get(3).unwrap().as_str();
+            if overlaps_itself(pattern) {
+                return Err(l.error(pattern, "a letter or a substring that can't overlap itself"));
+            }
+            Ok((
                 PasswordPolicy {
-                    lower: captures[1].parse().unwrap(),
-                    upper: captures[2].parse().unwrap(),
-                    letter: captures[3].chars().next().unwrap(),
+                    lower: l.parse(lower, "a lower bound")?,
+                    upper: l.parse(upper, "an upper bound")?,
+                    pattern: pattern.into(),
                 },
                 captures[4].into(),
-            )
+            ))
         })
         .collect()
 }
 
-#[aoc(day2, part1)]
-pub fn solve_part1(input: &[(PasswordPolicy, String)]) -> u32 {
-    input
+pub fn input_generator(input: &str) -> Vec<(PasswordPolicy, String)> {
+    try_input_generator(input).unwrap()
+}
+
+/// Whether a password complies with a policy, and why.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Verdict {
+    pub valid: bool,
+    pub reason: String,
+}
+
+/// A way of reading a [`PasswordPolicy`].
+pub trait Policy: Sync {
+    /// The name to select this interpretation by.
+    fn name(&self) -> &'static str;
+
+    fn check(&self, policy: &PasswordPolicy, password: &str) -> Verdict;
+}
+
+/// The pattern must appear between `lower` and `upper` times.
+pub struct Count;
+
+impl Policy for Count {
+    fn name(&self) -> &'static str {
+        "count"
+    }
+
+    fn check(&self, policy: &PasswordPolicy, password: &str) -> Verdict {
+        let count = password.matches(policy.pattern.as_str()).count();
+        Verdict {
+            valid: (policy.lower..=policy.upper).contains(&count),
+            reason: format!(
+                "{} appears {} time{}, allowed {}-{}",
+                policy.subject(),
+                count,
+                if count == 1 { "" } else { "s" },
+                policy.lower,
+                policy.upper
+            ),
+        }
+    }
+}
+
+/// Exactly one of the 1-based positions `lower` and `upper` must start the
+/// pattern. A position too near the end of the password to fit it doesn't.
+pub struct Positional;
+
+impl Policy for Positional {
+    fn name(&self) -> &'static str {
+        "positional"
+    }
+
+    fn check(&self, policy: &PasswordPolicy, password: &str) -> Verdict {
+        // Passwords are lowercase ASCII, so characters and bytes line up.
+        let at = |position: usize| {
+            // Position 0 is before the password, so it holds nothing.
+            let start = position
+                .checked_sub(1)
+                .map_or(password.len(), |i| i.min(password.len()));
+            &password[start..(start + policy.pattern.len()).min(password.len())]
+        };
+        let describe = |held: &str| {
+            if held.is_empty() {
+                "nothing".into()
+            } else {
+                format!("'{}'", held)
+            }
+        };
+
+        let (first, second) = (at(policy.lower), at(policy.upper));
+        Verdict {
+            valid: (first == policy.pattern) != (second == policy.pattern),
+            reason: format!(
+                "positions {} and {} hold {} and {}, exactly one must be '{}'",
+                policy.lower,
+                policy.upper,
+                describe(first),
+                describe(second),
+                policy.pattern
+            ),
+        }
+    }
+}
+
+/// Every interpretation, with part 1's first.
+pub static POLICIES: &[&dyn Policy] = &[&Count, &Positional];
+
+/// Looks up an interpretation by its name.
+pub fn find_policy(name: &str) -> Option<&'static dyn Policy> {
+    POLICIES
         .iter()
-        .map(|(policy, s)| {
-            let count: usize = s.chars().map(|c| (c == policy.letter) as usize).sum();
-            (!(count > policy.upper || count < policy.lower)) as u32
-        })
-        .sum()
+        .copied()
+        .find(|policy| policy.name() == name)
 }
 
-fn char_at(s: &str, i: usize) -> char {
-    s.chars().skip(i - 1).next().unwrap()
+/// Checks every entry against `policy`, in order.
+pub fn audit(input: &[(PasswordPolicy, String)], policy: &dyn Policy) -> Vec<Verdict> {
+    input
+        .iter()
+        .map(|(entry, password)| policy.check(entry, password))
+        .collect()
 }
 
-#[aoc(day2, part2)]
-pub fn solve_part2(input: &[(PasswordPolicy, String)]) -> u32 {
+fn count_valid(input: &[(PasswordPolicy, String)], policy: &dyn Policy) -> usize {
     input
         .iter()
-        .map(|(policy, s)| {
-            ((char_at(s, policy.lower) == policy.letter)
-                ^ (char_at(s, policy.upper) == policy.letter)) as u32
+        .filter(|(entry, password)| policy.check(entry, password).valid)
+        .count()
+}
+
+/// An entry with its verdict under every interpretation, in [`POLICIES`]
+/// order.
+pub struct AuditRow<'a> {
+    pub policy: &'a PasswordPolicy,
+    pub password: &'a str,
+    pub verdicts: Vec<Verdict>,
+}
+
+/// How many of the entries sharing a key fail each interpretation, in
+/// [`POLICIES`] order.
+pub struct FailureRate {
+    pub key: String,
+    pub entries: usize,
+    pub failures: Vec<usize>,
+}
+
+impl FailureRate {
+    pub fn rate(&self, policy: usize) -> f64 {
+        self.failures[policy] as f64 / self.entries as f64
+    }
+}
+
+/// Every entry checked against every interpretation, with failure rates by
+/// pattern and by the gap between the bounds.
+pub struct AuditReport<'a> {
+    pub rows: Vec<AuditRow<'a>>,
+    pub by_pattern: Vec<FailureRate>,
+    pub by_width: Vec<FailureRate>,
+}
+
+impl<'a> AuditReport<'a> {
+    pub fn new(input: &'a [(PasswordPolicy, String)]) -> Self {
+        let rows = input
+            .iter()
+            .map(|(policy, password)| AuditRow {
+                policy,
+                password,
+                verdicts: POLICIES
+                    .iter()
+                    .map(|interpretation| interpretation.check(policy, password))
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        let by_pattern = failure_rates(&rows, |row| row.policy.pattern.clone());
+        let by_width = failure_rates(&rows, |row| row.policy.upper.abs_diff(row.policy.lower));
+
+        Self {
+            rows,
+            by_pattern,
+            by_width,
+        }
+    }
+}
+
+fn failure_rates<K: Ord + Display>(
+    rows: &[AuditRow],
+    key: impl Fn(&AuditRow) -> K,
+) -> Vec<FailureRate> {
+    let mut groups = BTreeMap::new();
+    for row in rows {
+        let (entries, failures) = groups
+            .entry(key(row))
+            .or_insert_with(|| (0, vec![0; POLICIES.len()]));
+        *entries += 1;
+        for (failed, verdict) in failures.iter_mut().zip(&row.verdicts) {
+            *failed += !verdict.valid as usize;
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, (entries, failures))| FailureRate {
+            key: key.to_string(),
+            entries,
+            failures,
         })
-        .sum()
+        .collect()
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
+    }
+}
+
+/// Renders an audit as CSV: a table of entries, a blank line, then a table of
+/// failure rates grouped by pattern and by bound width.
+pub struct Csv<'a>(pub &'a AuditReport<'a>);
+
+impl Display for Csv<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "policy,password")?;
+        for policy in POLICIES {
+            write!(f, ",{0},{0}_reason", policy.name())?;
+        }
+        writeln!(f)?;
+        for row in &self.0.rows {
+            write!(f, "{},{}", csv_field(&row.policy.to_string()), row.password)?;
+            for verdict in &row.verdicts {
+                let valid = if verdict.valid { "valid" } else { "invalid" };
+                write!(f, ",{},{}", valid, csv_field(&verdict.reason))?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(f, "\ngroup,key,entries")?;
+        for policy in POLICIES {
+            write!(f, ",{0}_failures,{0}_failure_rate", policy.name())?;
+        }
+        writeln!(f)?;
+        let groups = [("pattern", &self.0.by_pattern), ("width", &self.0.by_width)];
+        for (group, rates) in groups.iter() {
+            for rate in rates.iter() {
+                write!(f, "{},{},{}", group, rate.key, rate.entries)?;
+                for (i, failures) in rate.failures.iter().enumerate() {
+                    write!(f, ",{},{:.4}", failures, rate.rate(i))?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn json_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Renders an audit as a JSON object with `entries`, `by_pattern` and
+/// `by_width` arrays.
+pub struct Json<'a>(pub &'a AuditReport<'a>);
+
+impl Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self
+            .0
+            .rows
+            .iter()
+            .map(|row| {
+                let verdicts = POLICIES
+                    .iter()
+                    .zip(&row.verdicts)
+                    .map(|(policy, verdict)| {
+                        format!(
+                            r#""{}":{{"valid":{},"reason":{}}}"#,
+                            policy.name(),
+                            verdict.valid,
+                            json_string(&verdict.reason)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(
+                    r#"{{"policy":{},"password":{},{}}}"#,
+                    json_string(&row.policy.to_string()),
+                    json_string(row.password),
+                    verdicts
+                )
+            })
+            .collect::<Vec<_>>();
+        let rates = |rates: &[FailureRate]| {
+            rates
+                .iter()
+                .map(|rate| {
+                    let failures = POLICIES
+                        .iter()
+                        .enumerate()
+                        .map(|(i, policy)| {
+                            format!(
+                                r#""{}":{{"failures":{},"rate":{:.4}}}"#,
+                                policy.name(),
+                                rate.failures[i],
+                                rate.rate(i)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    format!(
+                        r#"{{"key":{},"entries":{},{}}}"#,
+                        json_string(&rate.key),
+                        rate.entries,
+                        failures
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",\n    ")
+        };
+
+        writeln!(
+            f,
+            "{{\n  \"entries\": [\n    {}\n  ],\n  \"by_pattern\": [\n    {}\n  ],\n  \"by_width\": [\n    {}\n  ]\n}}",
+            entries.join(",\n    "),
+            rates(&self.0.by_pattern),
+            rates(&self.0.by_width)
+        )
+    }
+}
+
+/// Which interpretations a password has to satisfy.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Compliance {
+    Count,
+    Positional,
+    Both,
+}
+
+/// Where [`Passwords`] is while writing a password: how much of the pattern
+/// it has just written, how often the pattern appeared (capped just past the
+/// upper bound), and whether it appeared at either position.
+#[derive(Copy, Clone)]
+struct Progress {
+    matched: usize,
+    count: usize,
+    first: bool,
+    second: bool,
+}
+
+/// Every password of a given length over an alphabet that complies with a
+/// policy, counted position by position rather than one by one.
+pub struct Passwords {
+    alphabet: Vec<char>,
+    /// `next[matched][c]` is how much of the pattern has just been written
+    /// after writing `alphabet[c]` with `matched` of it written before.
+    next: Vec<Vec<usize>>,
+    pattern_len: usize,
+    /// The 0-based index each position's occurrence would end at.
+    ends: [Option<usize>; 2],
+    max_count: usize,
+    /// `completions[i][state]` is how many ways the rest of a password can be
+    /// written after its first `i` characters, ending up compliant.
+    completions: Vec<Vec<u128>>,
+}
+
+impl Passwords {
+    /// Counts the passwords of `length` characters from `alphabet` that
+    /// satisfy `compliance`. Returns `None` if the alphabet isn't all ASCII,
+    /// which patterns are matched byte by byte against, or if there are more
+    /// passwords than fit in a `u128`.
+    pub fn new(
+        policy: &PasswordPolicy,
+        alphabet: &[char],
+        length: usize,
+        compliance: Compliance,
+    ) -> Option<Self> {
+        if !alphabet.iter().all(char::is_ascii) {
+            return None;
+        }
+        let mut alphabet = alphabet.to_vec();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        // The pattern can't overlap itself, so after a match nothing of it
+        // carries over, and otherwise the longest suffix written that starts
+        // the pattern does.
+        let pattern = policy.pattern.as_bytes();
+        let next = (0..pattern.len())
+            .map(|matched| {
+                alphabet
+                    .iter()
+                    .map(|&c| {
+                        let mut written = pattern[..matched].to_vec();
+                        written.push(c as u8);
+                        if written == pattern {
+                            return pattern.len();
+                        }
+                        (0..written.len())
+                            .find(|&skip| pattern.starts_with(&written[skip..]))
+                            .map_or(0, |skip| written.len() - skip)
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+
+        let end = |position: usize| {
+            position
+                .checked_sub(1)
+                .map(|start| start + pattern.len() - 1)
+                .filter(|&end| end < length)
+        };
+        let mut passwords = Self {
+            alphabet,
+            next,
+            pattern_len: pattern.len(),
+            ends: [end(policy.lower), end(policy.upper)],
+            max_count: policy.upper.min(length / pattern.len()) + 1,
+            completions: vec![],
+        };
+
+        let complies = |state: Progress| {
+            let count = (policy.lower..=policy.upper).contains(&state.count);
+            let positional = state.first != state.second;
+            match compliance {
+                Compliance::Count => count,
+                Compliance::Positional => positional,
+                Compliance::Both => count && positional,
+            }
+        };
+        let mut completions = vec![vec![0; passwords.states()]; length + 1];
+        for (index, ways) in completions[length].iter_mut().enumerate() {
+            *ways = complies(passwords.state(index)) as u128;
+        }
+        for i in (0..length).rev() {
+            for index in 0..passwords.states() {
+                let mut ways = 0u128;
+                for c in 0..passwords.alphabet.len() {
+                    let after = passwords.index(passwords.write(passwords.state(index), c, i));
+                    ways = ways.checked_add(completions[i + 1][after])?;
+                }
+                completions[i][index] = ways;
+            }
+        }
+
+        passwords.completions = completions;
+        Some(passwords)
+    }
+
+    fn states(&self) -> usize {
+        self.pattern_len * (self.max_count + 1) * 4
+    }
+
+    fn index(&self, state: Progress) -> usize {
+        ((state.matched * (self.max_count + 1) + state.count) * 2 + state.first as usize) * 2
+            + state.second as usize
+    }
+
+    fn state(&self, index: usize) -> Progress {
+        Progress {
+            second: index % 2 == 1,
+            first: index / 2 % 2 == 1,
+            count: index / 4 % (self.max_count + 1),
+            matched: index / 4 / (self.max_count + 1),
+        }
+    }
+
+    /// Writes `alphabet[c]` as the character at 0-based index `i`.
+    fn write(&self, mut state: Progress, c: usize, i: usize) -> Progress {
+        state.matched = self.next[state.matched][c];
+        if state.matched == self.pattern_len {
+            state.matched = 0;
+            state.count = (state.count + 1).min(self.max_count);
+            state.first |= self.ends[0] == Some(i);
+            state.second |= self.ends[1] == Some(i);
+        }
+        state
+    }
+
+    pub fn count(&self) -> u128 {
+        self.completions[0][0]
+    }
+
+    /// A compliant password chosen uniformly at random, if there are any.
+    pub fn sample(&self, rng: &mut Rng) -> Option<String> {
+        if self.count() == 0 {
+            return None;
+        }
+
+        let mut password = String::new();
+        let mut state = self.state(0);
+        for i in 0..self.completions.len() - 1 {
+            let mut pick = below(rng, self.completions[i][self.index(state)]);
+            for c in 0..self.alphabet.len() {
+                let after = self.write(state, c, i);
+                let ways = self.completions[i + 1][self.index(after)];
+                if pick < ways {
+                    password.push(self.alphabet[c]);
+                    state = after;
+                    break;
+                }
+                pick -= ways;
+            }
+        }
+
+        Some(password)
+    }
+}
+
+/// A value below `bound` with no modulo bias, so that samples are uniform.
+fn below(rng: &mut Rng, bound: u128) -> u128 {
+    let zone = u128::MAX - u128::MAX % bound;
+    loop {
+        let value = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+        if value < zone {
+            return value % bound;
+        }
+    }
+}
+
+#[aoc(day2, part1)]
+pub fn solve_part1(input: &[(PasswordPolicy, String)]) -> usize {
+    count_valid(input, &Count)
+}
+
+#[aoc(day2, part2)]
+pub fn solve_part2(input: &[(PasswordPolicy, String)]) -> usize {
+    count_valid(input, &Positional)
+}
+
+#[test]
+fn test_audit() {
+    let input = input_generator("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n1-9 z: abc");
+    let verdicts = audit(&input, find_policy("count").unwrap());
+    assert_eq!(
+        verdicts[1],
+        Verdict {
+            valid: false,
+            reason: "letter 'b' appears 0 times, allowed 1-3".into()
+        }
+    );
+    assert!(verdicts[2].valid);
+
+    let verdicts = audit(&input, find_policy("positional").unwrap());
+    assert_eq!(
+        verdicts[0].reason,
+        "positions 1 and 3 hold 'a' and 'c', exactly one must be 'a'"
+    );
+    assert!(verdicts[0].valid);
+    assert!(!verdicts[2].valid);
+    assert_eq!(
+        verdicts[3].reason,
+        "positions 1 and 9 hold 'a' and nothing, exactly one must be 'z'"
+    );
+    assert!(find_policy("regex").is_none());
+}
+
+#[test]
+fn test_substrings() {
+    let input = input_generator("1-2 ab: abcab\n2-3 ab: abcab\n1-4 abc: xabcab");
+    let count = find_policy("count").unwrap();
+    let verdicts = audit(&input, count);
+    assert_eq!(
+        verdicts[0].reason,
+        "substring 'ab' appears 2 times, allowed 1-2"
+    );
+    assert!(verdicts[0].valid && verdicts[1].valid && verdicts[2].valid);
+
+    let verdicts = audit(&input, find_policy("positional").unwrap());
+    assert!(verdicts[0].valid);
+    assert_eq!(
+        verdicts[1].reason,
+        "positions 2 and 3 hold 'bc' and 'ca', exactly one must be 'ab'"
+    );
+    assert!(!verdicts[1].valid);
+    assert_eq!(
+        verdicts[2].reason,
+        "positions 1 and 4 hold 'xab' and 'cab', exactly one must be 'abc'"
+    );
+    assert!(!verdicts[2].valid);
+}
+
+#[test]
+fn test_parse_errors() {
+    let e = try_input_generator("1-3 a: abcde\n1-3 aba: abcde")
+        .err()
+        .unwrap();
+    assert_eq!((e.line(), e.column()), (2, 5));
+    let e = try_input_generator("1-3 aa: aaaa").err().unwrap();
+    assert_eq!(e.column(), 5);
+    let e = try_input_generator("1-3 A: abcde").err().unwrap();
+    assert_eq!(e.column(), 1);
+}
+
+#[test]
+fn test_audit_report() {
+    let input = input_generator("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc");
+    let report = AuditReport::new(&input);
+    assert_eq!(
+        Csv(&report).to_string(),
+        "policy,password,count,count_reason,positional,positional_reason
+1-3 a,abcde,valid,\"letter 'a' appears 1 time, allowed 1-3\",valid,\"positions 1 and 3 hold 'a' and 'c', exactly one must be 'a'\"
+1-3 b,cdefg,invalid,\"letter 'b' appears 0 times, allowed 1-3\",invalid,\"positions 1 and 3 hold 'c' and 'e', exactly one must be 'b'\"
+2-9 c,ccccccccc,valid,\"letter 'c' appears 9 times, allowed 2-9\",invalid,\"positions 2 and 9 hold 'c' and 'c', exactly one must be 'c'\"
+
+group,key,entries,count_failures,count_failure_rate,positional_failures,positional_failure_rate
+pattern,a,1,0,0.0000,0,0.0000
+pattern,b,1,1,1.0000,1,1.0000
+pattern,c,1,0,0.0000,1,1.0000
+width,2,2,1,0.5000,1,0.5000
+width,7,1,0,0.0000,1,1.0000
+"
+    );
+
+    let json = Json(&report).to_string();
+    assert!(json.starts_with(
+        r#"{
+  "entries": [
+    {"policy":"1-3 a","password":"abcde","count":{"valid":true,"reason":"letter 'a' appears 1 time, allowed 1-3"},"positional":"#
+    ));
+    assert!(json.contains(
+        r#"{"key":"2","entries":2,"count":{"failures":1,"rate":0.5000},"positional":{"failures":1,"rate":0.5000}}"#
+    ));
+    assert!(json.ends_with("  ]\n}\n"));
+}
+
+#[test]
+fn test_compliant_passwords() {
+    let alphabets: [&[char]; 3] = [&['a', 'b', 'c'], &['a', 'b'], &['x', 'y']];
+    let input = input_generator("1-3 a: x\n0-2 b: x\n2-4 ab: x\n3-1 a: x\n1-2 ba: x");
+    let mut rng = Rng::new(7);
+    for (policy, _) in &input {
+        for alphabet in alphabets.iter() {
+            for length in 0..=6 {
+                // Every password of this length over the alphabet.
+                let mut all = vec![String::new()];
+                for _ in 0..length {
+                    all = all
+                        .iter()
+                        .flat_map(|password| {
+                            alphabet.iter().map(move |&c| format!("{}{}", password, c))
+                        })
+                        .collect();
+                }
+
+                for &compliance in &[Compliance::Count, Compliance::Positional, Compliance::Both] {
+                    let complies = |password: &String| {
+                        let count = Count.check(policy, password).valid;
+                        let positional = Positional.check(policy, password).valid;
+                        match compliance {
+                            Compliance::Count => count,
+                            Compliance::Positional => positional,
+                            Compliance::Both => count && positional,
+                        }
+                    };
+                    let passwords = Passwords::new(policy, alphabet, length, compliance).unwrap();
+                    let expected = all.iter().filter(|password| complies(password)).count();
+                    assert_eq!(
+                        passwords.count(),
+                        expected as u128,
+                        "{} {:?}",
+                        policy,
+                        compliance
+                    );
+                    match passwords.sample(&mut rng) {
+                        Some(password) => {
+                            assert_eq!(password.len(), length);
+                            assert!(complies(&password), "{} {}", policy, password);
+                        }
+                        None => assert_eq!(expected, 0),
+                    }
+                }
+            }
+        }
+    }
+
+    let input = input_generator("0-100 a: x");
+    let letters = ('a'..='z').collect::<Vec<_>>();
+    let passwords = Passwords::new(&input[0].0, &letters, 27, Compliance::Count).unwrap();
+    assert_eq!(passwords.count(), 26u128.pow(27));
+    assert!(Passwords::new(&input[0].0, &letters, 28, Compliance::Count).is_none());
+    assert!(Passwords::new(&input[0].0, &['a', 'é'], 3, Compliance::Count).is_none());
 }
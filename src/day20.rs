@@ -0,0 +1,346 @@
+use crate::grid::Grid;
+use crate::parse::{self, ParseError};
+use aoc_runner_derive::aoc;
+use aoc_runner_derive::aoc_generator;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Pixel {
+    Off,
+    On,
+}
+
+impl TryFrom<char> for Pixel {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '.' => Ok(Pixel::Off),
+            '#' => Ok(Pixel::On),
+            _ => Err(value),
+        }
+    }
+}
+
+impl From<Pixel> for char {
+    fn from(pixel: Pixel) -> char {
+        match pixel {
+            Pixel::Off => '.',
+            Pixel::On => '#',
+        }
+    }
+}
+
+pub struct Tile {
+    id: u64,
+    image: Grid<Pixel>,
+}
+
+/// A tile in one of its orientations, along with its edges, read left to
+/// right and top to bottom.
+#[derive(Clone)]
+struct Oriented {
+    tile: usize,
+    image: Grid<Pixel>,
+    top: Vec<Pixel>,
+    bottom: Vec<Pixel>,
+    left: Vec<Pixel>,
+    right: Vec<Pixel>,
+}
+
+impl Oriented {
+    fn new(tile: usize, image: Grid<Pixel>) -> Self {
+        let (last_x, last_y) = (image.width() - 1, image.height() - 1);
+        let column = |x| (0..image.height()).map(|y| image[(x, y)]).collect();
+        let (left, right) = (column(0), column(last_x));
+        let row = |y| (0..image.width()).map(|x| image[(x, y)]).collect();
+        let (top, bottom) = (row(0), row(last_y));
+        Self {
+            tile,
+            top,
+            bottom,
+            left,
+            right,
+            image,
+        }
+    }
+}
+
+const MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+#[aoc_generator(day20)]
+pub fn try_input_generator(input: &str) -> Result<Vec<Tile>, ParseError> {
+    let mut tiles: Vec<Tile> = vec![];
+    let mut lines = parse::lines(20, input);
+    let mut last = None;
+    while let Some(header) = lines.next() {
+        let id = header
+            .text()
+            .strip_prefix("Tile ")
+            .and_then(|rest| rest.strip_suffix(':'))
+            .ok_or_else(|| header.error(header.text(), "`Tile <id>:`"))?;
+        let id = header.parse(id, "a tile id")?;
+
+        let rows = (&mut lines)
+            .take_while(|line| !line.text().is_empty())
+            .collect::<Vec<_>>();
+        let size = match rows.first() {
+            Some(row) => row.text().chars().count(),
+            None => return Err(header.missing("the rows of the tile")),
+        };
+        if size < 3 {
+            return Err(rows[0].error(rows[0].text(), "a row of at least 3 pixels"));
+        }
+        if rows.len() != size {
+            return Err(header.error(
+                header.text(),
+                format!("a tile with as many rows as its width of {}", size),
+            ));
+        }
+        if let Some(first) = tiles.first() {
+            if size != first.image.width() {
+                return Err(header.error(
+                    header.text(),
+                    format!("a tile of {0}x{0} like the first", first.image.width()),
+                ));
+            }
+        }
+
+        let mut cells = Vec::with_capacity(size * size);
+        for row in &rows {
+            if row.text().chars().count() != size {
+                return Err(row.error(row.text(), format!("a row of {} pixels", size)));
+            }
+            for (i, c) in row.text().char_indices() {
+                cells.push(Pixel::try_from(c).map_err(|_| row.char_error(i, "`#` or `.`"))?);
+            }
+        }
+
+        tiles.push(Tile {
+            id,
+            image: Grid::new(size, cells),
+        });
+        last = rows.last().copied();
+    }
+
+    let last = last.ok_or_else(|| parse::Line::new(20, 0, input).missing("`Tile <id>:`"))?;
+    if side(tiles.len()).is_none() {
+        return Err(last.missing(format!("a square number of tiles, found {}", tiles.len())));
+    }
+
+    Ok(tiles)
+}
+
+pub fn input_generator(input: &str) -> Vec<Tile> {
+    try_input_generator(input).unwrap()
+}
+
+/// The number of tiles along each side of a square of `count` tiles.
+fn side(count: usize) -> Option<usize> {
+    (1..=count)
+        .find(|side| side * side >= count)
+        .filter(|side| side * side == count)
+}
+
+/// The orientations of every tile, indexed by their left and top edges.
+struct Assembly<'a> {
+    side: usize,
+    oriented: &'a [Oriented],
+    by_left: HashMap<&'a [Pixel], Vec<usize>>,
+    by_top: HashMap<&'a [Pixel], Vec<usize>>,
+}
+
+impl<'a> Assembly<'a> {
+    fn new(side: usize, oriented: &'a [Oriented]) -> Self {
+        let mut by_left = HashMap::<_, Vec<_>>::new();
+        let mut by_top = HashMap::<_, Vec<_>>::new();
+        for (i, tile) in oriented.iter().enumerate() {
+            by_left.entry(tile.left.as_slice()).or_default().push(i);
+            by_top.entry(tile.top.as_slice()).or_default().push(i);
+        }
+
+        Self {
+            side,
+            oriented,
+            by_left,
+            by_top,
+        }
+    }
+
+    /// Every orientation, starting with those whose top and left edges match
+    /// no other tile and so are most likely in the top left corner.
+    fn corners_first(&self) -> Vec<usize> {
+        let unmatched = |edges: &HashMap<&[Pixel], Vec<usize>>, edge: &Vec<Pixel>, tile| {
+            edges[edge.as_slice()]
+                .iter()
+                .all(|&i| self.oriented[i].tile == tile)
+        };
+        let mut candidates = (0..self.oriented.len()).collect::<Vec<_>>();
+        candidates.sort_by_key(|&i| {
+            let oriented = &self.oriented[i];
+            !(unmatched(&self.by_left, &oriented.left, oriented.tile)
+                && unmatched(&self.by_top, &oriented.top, oriented.tile))
+        });
+        candidates
+    }
+
+    /// Fills the square in row order after the orientations already `placed`,
+    /// backtracking when no unused tile fits.
+    fn place(&self, used: &mut [bool], placed: &mut Vec<usize>) -> bool {
+        let position = placed.len();
+        if position == self.side * self.side {
+            return true;
+        }
+
+        let above = position
+            .checked_sub(self.side)
+            .map(|above| &self.oriented[placed[above]]);
+        let candidates = if !position.is_multiple_of(self.side) {
+            let left = &self.oriented[placed[position - 1]];
+            self.by_left.get(left.right.as_slice())
+        } else if let Some(above) = above {
+            self.by_top.get(above.bottom.as_slice())
+        } else {
+            None
+        };
+        let candidates = match candidates {
+            Some(candidates) => candidates.clone(),
+            None if position == 0 => self.corners_first(),
+            None => return false,
+        };
+
+        for candidate in candidates {
+            let tile = self.oriented[candidate].tile;
+            if used[tile] || above.is_some_and(|above| above.bottom != self.oriented[candidate].top)
+            {
+                continue;
+            }
+
+            used[tile] = true;
+            placed.push(candidate);
+            if self.place(used, placed) {
+                return true;
+            }
+            placed.pop();
+            used[tile] = false;
+        }
+
+        false
+    }
+}
+
+/// Arranges the tiles into a square in which every pair of neighbouring edges
+/// matches, if there is one.
+fn assemble(tiles: &[Tile]) -> Option<Grid<Oriented>> {
+    let side = side(tiles.len())?;
+    let oriented = tiles
+        .iter()
+        .enumerate()
+        .flat_map(|(i, tile)| {
+            tile.image
+                .orientations()
+                .into_iter()
+                .map(move |image| Oriented::new(i, image))
+        })
+        .collect::<Vec<_>>();
+
+    let mut used = vec![false; tiles.len()];
+    let mut placed = Vec::with_capacity(tiles.len());
+    if !Assembly::new(side, &oriented).place(&mut used, &mut placed) {
+        return None;
+    }
+
+    let cells = placed.into_iter().map(|i| oriented[i].clone()).collect();
+    Some(Grid::new(side, cells))
+}
+
+/// Joins the assembled tiles into one image, without their borders.
+fn join(arrangement: &Grid<Oriented>) -> Grid<Pixel> {
+    let size = arrangement[(0, 0)].image.width() - 2;
+    let width = arrangement.width() * size;
+    let cells = (0..arrangement.height() * size)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| arrangement[(x / size, y / size)].image[(x % size + 1, y % size + 1)])
+        .collect();
+    Grid::new(width, cells)
+}
+
+/// Counts the pixels that are on but not part of a sea monster, in whichever
+/// orientation of the image has any monsters.
+fn roughness(image: &Grid<Pixel>) -> usize {
+    let monster = MONSTER
+        .iter()
+        .enumerate()
+        .flat_map(|(dy, row)| {
+            row.char_indices()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(dx, _)| (dx, dy))
+        })
+        .collect::<Vec<_>>();
+
+    let on = image.iter().filter(|&&pixel| pixel == Pixel::On).count();
+    for image in image.orientations() {
+        let mut in_monster = image.map(|_| false);
+        for (x, y) in image.positions() {
+            if monster
+                .iter()
+                .all(|&(dx, dy)| image.get(x + dx, y + dy) == Some(&Pixel::On))
+            {
+                for &(dx, dy) in &monster {
+                    in_monster[(x + dx, y + dy)] = true;
+                }
+            }
+        }
+
+        let covered = in_monster.iter().filter(|&&covered| covered).count();
+        if covered > 0 {
+            return on - covered;
+        }
+    }
+
+    on
+}
+
+#[aoc(day20, part1)]
+pub fn solve_part1(input: &[Tile]) -> u64 {
+    let arrangement = assemble(input).expect("the tiles do not fit together");
+    let last = arrangement.width() - 1;
+    [(0, 0), (last, 0), (0, last), (last, last)]
+        .iter()
+        .map(|&corner| input[arrangement[corner].tile].id)
+        .product()
+}
+
+#[aoc(day20, part2)]
+pub fn solve_part2(input: &[Tile]) -> usize {
+    let arrangement = assemble(input).expect("the tiles do not fit together");
+    roughness(&join(&arrangement))
+}
+
+#[test]
+fn test_parse_errors() {
+    let expected = |input| {
+        try_input_generator(input)
+            .err()
+            .unwrap()
+            .expected()
+            .to_owned()
+    };
+    assert_eq!(expected(""), "`Tile <id>:`");
+    assert_eq!(expected("Tile one:\n#..\n.#.\n..#"), "a tile id");
+    assert_eq!(expected("Tile 1:\n#.\n.#"), "a row of at least 3 pixels");
+    assert_eq!(expected("Tile 1:\n#..\n.#.\n.."), "a row of 3 pixels");
+    assert_eq!(
+        expected("Tile 1:\n#..\n.#.\n..#\n#.."),
+        "a tile with as many rows as its width of 3"
+    );
+    assert_eq!(
+        expected("Tile 1:\n#..\n.#.\n..#\n\nTile 2:\n#..\n.#.\n..#"),
+        "a square number of tiles, found 2"
+    );
+}
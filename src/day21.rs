@@ -0,0 +1,120 @@
+use crate::parse::{self, ParseError};
+use aoc_runner_derive::aoc;
+use aoc_runner_derive::aoc_generator;
+use std::collections::{BTreeMap, HashSet};
+
+pub struct Food {
+    ingredients: Vec<String>,
+    allergens: Vec<String>,
+}
+
+#[aoc_generator(day21)]
+pub fn try_input_generator(input: &str) -> Result<Vec<Food>, ParseError> {
+    let mut foods = vec![];
+    for line in parse::lines(21, input) {
+        let mut parts = line.text().splitn(2, " (contains ");
+        let ingredients = parts.next().unwrap();
+        if ingredients.is_empty() {
+            return Err(line.missing("a list of ingredients"));
+        }
+
+        let allergens = match parts.next() {
+            Some(allergens) => allergens
+                .strip_suffix(')')
+                .ok_or_else(|| line.missing("`)`"))?
+                .split(", ")
+                .map(|allergen| {
+                    if allergen.is_empty() || allergen.contains(' ') {
+                        Err(line.error(allergen, "an allergen"))
+                    } else {
+                        Ok(allergen.into())
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+            None => vec![],
+        };
+
+        foods.push(Food {
+            ingredients: ingredients.split(' ').map(String::from).collect(),
+            allergens,
+        });
+    }
+
+    Ok(foods)
+}
+
+pub fn input_generator(input: &str) -> Vec<Food> {
+    try_input_generator(input).unwrap()
+}
+
+/// The ingredients that could contain each allergen: those in every food
+/// listing it.
+fn candidates(input: &[Food]) -> BTreeMap<&str, HashSet<&str>> {
+    let mut candidates = BTreeMap::<_, HashSet<_>>::new();
+    for food in input {
+        let ingredients = food.ingredients.iter().map(String::as_str);
+        for allergen in &food.allergens {
+            match candidates.get_mut(allergen.as_str()) {
+                Some(candidates) => {
+                    let ingredients = ingredients.clone().collect::<HashSet<_>>();
+                    candidates.retain(|ingredient| ingredients.contains(ingredient));
+                }
+                None => {
+                    candidates.insert(allergen.as_str(), ingredients.clone().collect());
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+#[aoc(day21, part1)]
+pub fn solve_part1(input: &[Food]) -> usize {
+    let candidates = candidates(input);
+    let unsafe_ingredients = candidates.values().flatten().collect::<HashSet<_>>();
+    input
+        .iter()
+        .flat_map(|food| &food.ingredients)
+        .filter(|ingredient| !unsafe_ingredients.contains(&ingredient.as_str()))
+        .count()
+}
+
+#[aoc(day21, part2)]
+pub fn solve_part2(input: &[Food]) -> String {
+    let mut candidates = candidates(input);
+    let mut dangerous = BTreeMap::new();
+
+    // Settle any allergen down to a single candidate, which no other
+    // allergen can then be in, until all are settled.
+    while !candidates.is_empty() {
+        let (allergen, ingredient) = candidates
+            .iter()
+            .find(|(_, ingredients)| ingredients.len() == 1)
+            .map(|(&allergen, ingredients)| (allergen, *ingredients.iter().next().unwrap()))
+            .expect("the allergens cannot be told apart");
+        candidates.remove(allergen);
+        for ingredients in candidates.values_mut() {
+            ingredients.remove(ingredient);
+        }
+        dangerous.insert(allergen, ingredient);
+    }
+
+    dangerous.values().copied().collect::<Vec<_>>().join(",")
+}
+
+#[test]
+fn test_parse_errors() {
+    let expected = |input| {
+        try_input_generator(input)
+            .err()
+            .unwrap()
+            .expected()
+            .to_owned()
+    };
+    assert_eq!(expected(" (contains dairy)"), "a list of ingredients");
+    assert_eq!(expected("a b (contains dairy\n"), "`)`");
+    assert_eq!(expected("a b (contains dairy, )"), "an allergen");
+    assert_eq!(expected("a b (contains dairy, soy milk)"), "an allergen");
+    assert_eq!(input_generator("a b")[0].allergens.len(), 0);
+}
@@ -0,0 +1,153 @@
+use crate::parse::{self, Line, ParseError};
+use aoc_runner_derive::aoc;
+use aoc_runner_derive::aoc_generator;
+use std::collections::{HashSet, VecDeque};
+
+type Deck = VecDeque<u8>;
+
+pub struct Decks {
+    one: Deck,
+    two: Deck,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Player {
+    One,
+    Two,
+}
+
+#[aoc_generator(day22)]
+pub fn try_input_generator(input: &str) -> Result<Decks, ParseError> {
+    let mut lines = parse::lines(22, input);
+    let mut deck = |header: &str| {
+        let line = lines
+            .next()
+            .ok_or_else(|| Line::new(22, 0, input).missing(format!("`{}`", header)))?;
+        if line.text() != header {
+            return Err(line.error(line.text(), format!("`{}`", header)));
+        }
+
+        (&mut lines)
+            .take_while(|line| !line.text().is_empty())
+            .map(|line| line.parse(line.text(), "a card from 0 to 255"))
+            .collect::<Result<Deck, _>>()
+    };
+
+    let one = deck("Player 1:")?;
+    let two = deck("Player 2:")?;
+    if let Some(line) = lines.next() {
+        return Err(line.error(line.text(), "the end of the input"));
+    }
+
+    Ok(Decks { one, two })
+}
+
+pub fn input_generator(input: &str) -> Decks {
+    try_input_generator(input).unwrap()
+}
+
+fn score(deck: &Deck) -> usize {
+    deck.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &card)| (i + 1) * card as usize)
+        .sum()
+}
+
+/// Plays a game of Recursive Combat to the end, returning the winner. Sub-games
+/// are played on copies of the top of each deck.
+fn recursive_combat(one: &mut Deck, two: &mut Deck, sub_game: bool) -> Player {
+    // Neither player can ever draw enough cards to recurse on the highest card
+    // in play, so whoever holds it never loses it. If that's player 1, the game
+    // ends with them winning either way, and only a sub-game can skip to that.
+    if sub_game {
+        let highest = |deck: &Deck| deck.iter().copied().max().unwrap_or(0);
+        if highest(one) > highest(two) && highest(one) as usize > one.len() + two.len() {
+            return Player::One;
+        }
+    }
+
+    let mut seen = HashSet::new();
+    while !one.is_empty() && !two.is_empty() {
+        if !seen.insert((one.clone(), two.clone())) {
+            return Player::One;
+        }
+
+        let (a, b) = (one.pop_front().unwrap(), two.pop_front().unwrap());
+        let winner = if a as usize <= one.len() && b as usize <= two.len() {
+            recursive_combat(
+                &mut one.iter().take(a as usize).copied().collect(),
+                &mut two.iter().take(b as usize).copied().collect(),
+                true,
+            )
+        } else if a > b {
+            Player::One
+        } else {
+            Player::Two
+        };
+
+        match winner {
+            Player::One => one.extend(&[a, b]),
+            Player::Two => two.extend(&[b, a]),
+        }
+    }
+
+    if one.is_empty() {
+        Player::Two
+    } else {
+        Player::One
+    }
+}
+
+#[aoc(day22, part1)]
+pub fn solve_part1(input: &Decks) -> usize {
+    let (mut one, mut two) = (input.one.clone(), input.two.clone());
+    while let (Some(&a), Some(&b)) = (one.front(), two.front()) {
+        one.pop_front();
+        two.pop_front();
+        if a > b {
+            one.extend(&[a, b]);
+        } else {
+            two.extend(&[b, a]);
+        }
+    }
+
+    score(&one) + score(&two)
+}
+
+#[aoc(day22, part2)]
+pub fn solve_part2(input: &Decks) -> usize {
+    let (mut one, mut two) = (input.one.clone(), input.two.clone());
+    match recursive_combat(&mut one, &mut two, false) {
+        Player::One => score(&one),
+        Player::Two => score(&two),
+    }
+}
+
+#[test]
+fn test_infinite_game() {
+    let input = input_generator("Player 1:\n43\n19\n\nPlayer 2:\n2\n29\n14");
+    let (mut one, mut two) = (input.one.clone(), input.two.clone());
+    assert_eq!(recursive_combat(&mut one, &mut two, false), Player::One);
+}
+
+#[test]
+fn test_parse_errors() {
+    let expected = |input| {
+        try_input_generator(input)
+            .err()
+            .unwrap()
+            .expected()
+            .to_owned()
+    };
+    assert_eq!(expected("Player 1:\n9\n2\n\nPlayer 3:\n5"), "`Player 2:`");
+    assert_eq!(expected("Player 1:\n9"), "`Player 2:`");
+    assert_eq!(
+        expected("Player 1:\n9\n256\n\nPlayer 2:\n5"),
+        "a card from 0 to 255"
+    );
+    assert_eq!(
+        expected("Player 1:\n9\n\nPlayer 2:\n5\n\nPlayer 3:\n1"),
+        "the end of the input"
+    );
+}
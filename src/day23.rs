@@ -0,0 +1,145 @@
+use crate::params::{Param, Params};
+use crate::parse::{self, Line, ParseError};
+use aoc_runner_derive::aoc;
+use aoc_runner_derive::aoc_generator;
+
+pub const PARAMS: &[Param] = &[
+    Param::new::<usize>("part1_moves", "100", "how many moves part 1 makes"),
+    Param::new::<usize>("part2_moves", "10000000", "how many moves part 2 makes"),
+    Param::new::<usize>(
+        "part2_cups",
+        "1000000",
+        "how many cups part 2 plays with, counting the labelled ones",
+    ),
+];
+
+#[aoc_generator(day23)]
+pub fn try_input_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut lines = parse::lines(23, input);
+    let line = lines
+        .next()
+        .ok_or_else(|| Line::new(23, 0, input).missing("a cup label"))?;
+    if let Some(extra) = lines.next() {
+        return Err(extra.error(extra.text(), "a single line of cup labels"));
+    }
+
+    let count = line.text().len() as u32;
+    let mut seen = vec![false; count as usize + 1];
+    let mut cups = vec![];
+    for (i, c) in line.text().char_indices() {
+        let label = c
+            .to_digit(10)
+            .filter(|&label| (1..=count).contains(&label))
+            .ok_or_else(|| line.char_error(i, format!("a cup label from 1 to {}", count)))?;
+        if seen[label as usize] {
+            return Err(line.char_error(i, "a label not seen before"));
+        }
+        seen[label as usize] = true;
+        cups.push(label);
+    }
+    // Each move needs a cup to pick the others up after, three to pick up and
+    // one more to put them down after.
+    if cups.len() < 5 {
+        return Err(line.missing("at least 5 cup labels"));
+    }
+
+    Ok(cups)
+}
+
+pub fn input_generator(input: &str) -> Vec<u32> {
+    try_input_generator(input).unwrap()
+}
+
+/// Makes `moves` moves with the labelled cups followed by the rest of the
+/// labels up to `cups`, all in a circle. Returns the label of the cup
+/// clockwise of each label, with index 0 unused.
+fn play(input: &[u32], cups: usize, moves: usize) -> Vec<u32> {
+    let cups = cups.max(input.len()) as u32;
+    let labels = input.iter().copied().chain(input.len() as u32 + 1..=cups);
+
+    // A circular linked list of labels, stored as each one's successor.
+    let mut next = vec![0; cups as usize + 1];
+    let mut previous = input[0];
+    for label in labels.skip(1) {
+        next[previous as usize] = label;
+        previous = label;
+    }
+    next[previous as usize] = input[0];
+
+    let mut current = input[0];
+    for _ in 0..moves {
+        let a = next[current as usize];
+        let b = next[a as usize];
+        let c = next[b as usize];
+
+        let mut destination = current;
+        loop {
+            destination = if destination == 1 {
+                cups
+            } else {
+                destination - 1
+            };
+            if destination != a && destination != b && destination != c {
+                break;
+            }
+        }
+
+        next[current as usize] = next[c as usize];
+        next[c as usize] = next[destination as usize];
+        next[destination as usize] = a;
+        current = next[current as usize];
+    }
+
+    next
+}
+
+#[aoc(day23, part1)]
+pub fn solve_part1(input: &[u32]) -> String {
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &[u32], params: &Params) -> String {
+    let next = play(input, input.len(), params.get("part1_moves"));
+    let mut labels = String::new();
+    let mut label = next[1];
+    while label != 1 {
+        labels.push(std::char::from_digit(label, 10).unwrap());
+        label = next[label as usize];
+    }
+    labels
+}
+
+#[aoc(day23, part2)]
+pub fn solve_part2(input: &[u32]) -> u64 {
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &[u32], params: &Params) -> u64 {
+    let next = play(input, params.get("part2_cups"), params.get("part2_moves"));
+    let a = next[1];
+    let b = next[a as usize];
+    a as u64 * b as u64
+}
+
+#[test]
+fn test_ten_moves() {
+    let input = input_generator("389125467");
+    let mut params = Params::defaults(PARAMS);
+    params.set("part1_moves", "10").unwrap();
+    assert_eq!(solve_part1_with(&input, &params), "92658374");
+}
+
+#[test]
+fn test_parse_errors() {
+    let expected = |input| {
+        try_input_generator(input)
+            .unwrap_err()
+            .expected()
+            .to_owned()
+    };
+    assert_eq!(expected("38912546x"), "a cup label from 1 to 9");
+    assert_eq!(expected("3891254"), "a cup label from 1 to 7");
+    assert_eq!(expected("31542313"), "a label not seen before");
+    assert_eq!(expected("3124"), "at least 5 cup labels");
+    assert_eq!(expected("38912\n54"), "a single line of cup labels");
+}
@@ -0,0 +1,171 @@
+use crate::params::{Param, Params};
+use crate::parse::{self, ParseError};
+use aoc_runner_derive::aoc;
+use aoc_runner_derive::aoc_generator;
+use std::collections::{HashMap, HashSet};
+
+/// A step to one of the six neighbouring hexagons.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Direction {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl Direction {
+    const ALL: [Direction; 6] = [
+        Direction::East,
+        Direction::SouthEast,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::NorthEast,
+    ];
+
+    /// The step in axial coordinates, where `q` grows to the east and `r` to
+    /// the south east.
+    fn offset(self) -> Hex {
+        match self {
+            Direction::East => Hex(1, 0),
+            Direction::SouthEast => Hex(0, 1),
+            Direction::SouthWest => Hex(-1, 1),
+            Direction::West => Hex(-1, 0),
+            Direction::NorthWest => Hex(0, -1),
+            Direction::NorthEast => Hex(1, -1),
+        }
+    }
+}
+
+/// A tile in axial coordinates `(q, r)`, relative to the reference tile.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+struct Hex(isize, isize);
+
+impl Hex {
+    fn step(self, direction: Direction) -> Self {
+        let offset = direction.offset();
+        Hex(self.0 + offset.0, self.1 + offset.1)
+    }
+
+    fn neighbors(self) -> impl Iterator<Item = Hex> {
+        Direction::ALL
+            .iter()
+            .map(move |&direction| self.step(direction))
+    }
+}
+
+pub const PARAMS: &[Param] = &[Param::at_least::<1>(
+    "days",
+    "100",
+    "how many days of flipping to simulate (at least 1)",
+)];
+
+#[aoc_generator(day24)]
+pub fn try_input_generator(input: &str) -> Result<Vec<Vec<Direction>>, ParseError> {
+    let mut paths = vec![];
+    for line in parse::lines(24, input) {
+        let mut path = vec![];
+        let mut chars = line.text().char_indices();
+        while let Some((i, c)) = chars.next() {
+            let direction = match c {
+                'e' => Direction::East,
+                'w' => Direction::West,
+                'n' | 's' => match (c, chars.next()) {
+                    ('s', Some((_, 'e'))) => Direction::SouthEast,
+                    ('s', Some((_, 'w'))) => Direction::SouthWest,
+                    ('n', Some((_, 'w'))) => Direction::NorthWest,
+                    ('n', Some((_, 'e'))) => Direction::NorthEast,
+                    (_, Some((j, _))) => return Err(line.char_error(j, "`e` or `w`")),
+                    (_, None) => return Err(line.missing("`e` or `w`")),
+                },
+                _ => return Err(line.char_error(i, "`e`, `se`, `sw`, `w`, `nw` or `ne`")),
+            };
+            path.push(direction);
+        }
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+pub fn input_generator(input: &str) -> Vec<Vec<Direction>> {
+    try_input_generator(input).unwrap()
+}
+
+/// The tiles left black after flipping the one at the end of each path.
+fn black_tiles(input: &[Vec<Direction>]) -> HashSet<Hex> {
+    let mut black = HashSet::new();
+    for path in input {
+        let tile = path
+            .iter()
+            .fold(Hex(0, 0), |tile, &direction| tile.step(direction));
+        if !black.remove(&tile) {
+            black.insert(tile);
+        }
+    }
+
+    black
+}
+
+#[aoc(day24, part1)]
+pub fn solve_part1(input: &[Vec<Direction>]) -> usize {
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &[Vec<Direction>], _params: &Params) -> usize {
+    black_tiles(input).len()
+}
+
+#[aoc(day24, part2)]
+pub fn solve_part2(input: &[Vec<Direction>]) -> usize {
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &[Vec<Direction>], params: &Params) -> usize {
+    let mut black = black_tiles(input);
+
+    for _ in 0..params.get::<usize>("days") {
+        let mut neighbors = HashMap::new();
+        for tile in &black {
+            for neighbor in tile.neighbors() {
+                *neighbors.entry(neighbor).or_insert(0) += 1;
+            }
+        }
+
+        black = neighbors
+            .into_iter()
+            .filter(|&(tile, count)| count == 2 || (count == 1 && black.contains(&tile)))
+            .map(|(tile, _)| tile)
+            .collect();
+    }
+
+    black.len()
+}
+
+#[test]
+fn test_path() {
+    let input = input_generator("nwwswee\nesew");
+    let tiles = input
+        .iter()
+        .map(|path| {
+            path.iter()
+                .fold(Hex(0, 0), |tile, &direction| tile.step(direction))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(tiles, [Hex(0, 0), Hex(0, 1)]);
+}
+
+#[test]
+fn test_parse_errors() {
+    let expected = |input| {
+        try_input_generator(input)
+            .unwrap_err()
+            .expected()
+            .to_owned()
+    };
+    assert_eq!(expected("esew\nexw"), "`e`, `se`, `sw`, `w`, `nw` or `ne`");
+    assert_eq!(expected("esxw"), "`e` or `w`");
+    assert_eq!(expected("wes"), "`e` or `w`");
+}
@@ -0,0 +1,98 @@
+use crate::parse::{self, Line, ParseError};
+use aoc_runner_derive::aoc;
+use aoc_runner_derive::aoc_generator;
+
+const MODULUS: u64 = 20201227;
+const SUBJECT: u64 = 7;
+
+pub struct PublicKeys {
+    card: u64,
+    door: u64,
+}
+
+#[aoc_generator(day25)]
+pub fn try_input_generator(input: &str) -> Result<PublicKeys, ParseError> {
+    let mut lines = parse::lines(25, input);
+    let mut key = |name: &str| {
+        let line = lines
+            .next()
+            .ok_or_else(|| Line::new(25, 0, input).missing(format!("the {} public key", name)))?;
+        line.parse(line.text(), "a public key")
+            .ok()
+            .filter(|&key| 0 < key && key < MODULUS)
+            .ok_or_else(|| line.error(line.text(), format!("a public key below {}", MODULUS)))
+    };
+
+    let card = key("card's")?;
+    let door = key("door's")?;
+    if let Some(line) = lines.next() {
+        return Err(line.error(line.text(), "the end of the input"));
+    }
+
+    Ok(PublicKeys { card, door })
+}
+
+pub fn input_generator(input: &str) -> PublicKeys {
+    try_input_generator(input).unwrap()
+}
+
+/// Transforms `subject` with the given loop size, which is `subject` raised
+/// to that power, by squaring.
+fn transform(subject: u64, loop_size: u64) -> u64 {
+    let (mut base, mut exponent, mut result) = (subject % MODULUS, loop_size, 1);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % MODULUS;
+        }
+        base = base * base % MODULUS;
+        exponent >>= 1;
+    }
+    result
+}
+
+#[aoc(day25, part1)]
+pub fn solve_part1(input: &PublicKeys) -> u64 {
+    // Step the subject-7 transform until it yields either key, so only the
+    // smaller of the two loop sizes is searched for.
+    let mut value = 1;
+    for loop_size in 1..MODULUS {
+        value = value * SUBJECT % MODULUS;
+        if value == input.card {
+            return transform(input.door, loop_size);
+        }
+        if value == input.door {
+            return transform(input.card, loop_size);
+        }
+    }
+
+    unreachable!("7 generates every public key below the modulus")
+}
+
+/// Day 25 only has one puzzle.
+#[aoc(day25, part2)]
+pub fn solve_part2(_input: &PublicKeys) -> &'static str {
+    "-"
+}
+
+#[test]
+fn test_transform() {
+    assert_eq!(transform(SUBJECT, 8), 5764801);
+    assert_eq!(transform(SUBJECT, 11), 17807724);
+    assert_eq!(transform(17807724, 8), 14897079);
+    assert_eq!(transform(5764801, 11), 14897079);
+}
+
+#[test]
+fn test_parse_errors() {
+    let expected = |input| {
+        try_input_generator(input)
+            .err()
+            .unwrap()
+            .expected()
+            .to_owned()
+    };
+    assert_eq!(expected("5764801\n20201227"), "a public key below 20201227");
+    assert_eq!(expected("0\n17807724"), "a public key below 20201227");
+    assert_eq!(expected("5764801"), "the door's public key");
+    assert_eq!(expected("5764801\n17807724\n1"), "the end of the input");
+}
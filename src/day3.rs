@@ -1,50 +1,223 @@
+use crate::grid::Grid;
+use crate::params::{Param, Params};
+use crate::parse::ParseError;
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
+use std::convert::TryFrom;
+use std::str::FromStr;
 
-const fn next_right(width: usize, x: usize, right: usize) -> usize {
-    (x + right) % width
+pub const PARAMS: &[Param] = &[
+    Param::new::<Slopes>(
+        "part1_slopes",
+        "3,1",
+        "the slopes part 1 multiplies trees over",
+    ),
+    Param::new::<Slopes>(
+        "part2_slopes",
+        "1,1 3,1 5,1 7,1 1,2",
+        "the slopes part 2 multiplies trees over",
+    ),
+];
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Square {
+    Open,
+    Tree,
 }
 
-#[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> Vec<Vec<bool>> {
-    input
-        .lines()
-        .map(|l| l.chars().map(|c| c == '#').collect::<Vec<_>>())
-        .collect()
+impl TryFrom<char> for Square {
+    type Error = ();
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
+            '.' => Square::Open,
+            '#' => Square::Tree,
+            _ => return Err(()),
+        })
+    }
 }
 
-#[aoc(day3, part1)]
-pub fn solve_part1(input: &[Vec<bool>]) -> u32 {
-    const START: usize = 0;
-    const RIGHT: usize = 3;
-    let mut pos = START;
-    input
-        .iter()
-        .skip(1)
-        .map(|line| {
-            pos = next_right(line.len(), pos, RIGHT);
-            line[pos] as u32
+impl From<Square> for char {
+    fn from(square: Square) -> char {
+        match square {
+            Square::Open => '.',
+            Square::Tree => '#',
+        }
+    }
+}
+
+/// A direction of travel, moving `right / denominator` columns (to the left
+/// when negative) for every `down` rows.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Slope {
+    right: isize,
+    denominator: usize,
+    down: usize,
+}
+
+impl Slope {
+    pub fn new(right: isize, denominator: usize, down: usize) -> Option<Self> {
+        if denominator == 0 || down == 0 {
+            return None;
+        }
+        Some(Self {
+            right,
+            denominator,
+            down,
         })
-        .sum()
+    }
 }
 
-#[aoc(day3, part2)]
-pub fn solve_part2(input: &[Vec<bool>]) -> u32 {
-    const START: usize = 0;
-    const TRAJECTORIES: [(usize, usize); 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
-    TRAJECTORIES
+impl FromStr for Slope {
+    type Err = ();
+
+    /// Parses `right,down`, where `right` may be negative or a fraction like
+    /// `1/3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (right, down) = s.split_once(',').ok_or(())?;
+        let (right, denominator) = right.split_once('/').unwrap_or((right, "1"));
+        Slope::new(
+            right.parse().map_err(drop)?,
+            denominator.parse().map_err(drop)?,
+            down.parse().map_err(drop)?,
+        )
+        .ok_or(())
+    }
+}
+
+/// Trajectories given as space separated slopes.
+pub struct Slopes(Vec<Slope>);
+
+impl FromStr for Slopes {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Slopes)
+    }
+}
+
+/// Where a trajectory went, with the map repeating to both sides.
+pub struct Trajectory {
+    pub trees: usize,
+    /// The cells visited, from the top, with columns within the map.
+    pub visited: Vec<(usize, usize)>,
+}
+
+/// Follows `slope` from the top left corner to the bottom of the map.
+///
+/// A whole number of columns per move hops from cell to cell like the
+/// puzzle's toboggan, skipping the rows and columns in between. Any other
+/// slope is drawn as a line from the top left corner of the start and visits
+/// every cell it passes through, however many that is in a row, including
+/// rows a hop would skip. A leftward line mirrors the rightward one so that it
+/// starts in the same cell.
+pub fn trajectory(map: &Grid<Square>, slope: Slope) -> Trajectory {
+    let mut visited = vec![];
+    let mut visit = |x: isize, y: usize| {
+        visited.push((x.rem_euclid(map.width() as isize) as usize, y));
+    };
+
+    let (right, denominator) = (slope.right, slope.denominator as isize);
+    if right % denominator == 0 {
+        for (step, y) in (0..map.height()).step_by(slope.down).enumerate() {
+            visit(step as isize * right / denominator, y);
+        }
+    } else {
+        // The line moves `run / rise` columns per row. Row `y` spans columns
+        // `run * y / rise` to `run * (y + 1) / rise`, and visits every cell
+        // it is inside of, not the one it only touches at the end.
+        let (run, rise) = (right.abs(), denominator * slope.down as isize);
+        for y in 0..map.height() {
+            let first = run * y as isize / rise;
+            let last = ((run * (y as isize + 1) + rise - 1) / rise - 1).max(first);
+            for x in first..=last {
+                visit(x * right.signum(), y);
+            }
+        }
+    }
+
+    Trajectory {
+        trees: visited
+            .iter()
+            .filter(|&&cell| map[cell] == Square::Tree)
+            .count(),
+        visited,
+    }
+}
+
+#[aoc_generator(day3)]
+pub fn try_input_generator(input: &str) -> Result<Grid<Square>, ParseError> {
+    Grid::parse(3, input, "`#` or `.`")
+}
+
+pub fn input_generator(input: &str) -> Grid<Square> {
+    try_input_generator(input).unwrap()
+}
+
+fn product_of_trees(map: &Grid<Square>, slopes: &Slopes) -> usize {
+    slopes
+        .0
         .iter()
-        .map(|&(right, down)| {
-            let mut pos = START;
-            input
-                .iter()
-                .step_by(down)
-                .map(|line| {
-                    let res = line[pos] as u32;
-                    pos = next_right(line.len(), pos, right);
-                    res
-                })
-                .sum::<u32>()
-        })
+        .map(|&slope| trajectory(map, slope).trees)
         .product()
 }
+
+#[aoc(day3, part1)]
+pub fn solve_part1(input: &Grid<Square>) -> usize {
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &Grid<Square>, params: &Params) -> usize {
+    product_of_trees(input, &params.get("part1_slopes"))
+}
+
+#[aoc(day3, part2)]
+pub fn solve_part2(input: &Grid<Square>) -> usize {
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &Grid<Square>, params: &Params) -> usize {
+    product_of_trees(input, &params.get("part2_slopes"))
+}
+
+#[test]
+fn test_trajectory() {
+    let map = input_generator("#..\n.#.\n..#\n#..");
+    let follow = |slope: &str| trajectory(&map, slope.parse().unwrap());
+
+    let left = follow("-1,1");
+    assert_eq!(left.trees, 2);
+    assert_eq!(left.visited, [(0, 0), (2, 1), (1, 2), (0, 3)]);
+    assert_eq!(follow("2/2,2").visited, follow("1,2").visited);
+
+    assert_eq!(follow("1/2,1").visited, [(0, 0), (0, 1), (1, 2), (1, 3)]);
+    // Row 1 spans columns 2/3 to 4/3, so the line passes through two cells.
+    assert_eq!(
+        follow("2/3,1").visited,
+        [(0, 0), (0, 1), (1, 1), (1, 2), (2, 3)]
+    );
+    let half_left = follow("-1/2,1");
+    assert_eq!(half_left.trees, 2);
+    assert_eq!(half_left.visited, [(0, 0), (0, 1), (2, 2), (2, 3)]);
+
+    for slope in &["1/0,1", "1,0", "1/-2,1", "x,1", "1"] {
+        assert!(slope.parse::<Slope>().is_err());
+    }
+}
+
+#[test]
+fn test_steep_line() {
+    let map = input_generator("#..\n#..\n#..\n.#.");
+    // Hopping three rows at a time skips the trees the line passes through.
+    assert_eq!(trajectory(&map, "1,3".parse().unwrap()).trees, 2);
+    let line = trajectory(&map, "1/3,1".parse().unwrap());
+    assert_eq!(line.trees, 4);
+    assert_eq!(line.visited, [(0, 0), (0, 1), (0, 2), (1, 3)]);
+    assert_eq!(
+        trajectory(&map, "1/2,3".parse().unwrap()).visited,
+        [(0, 0), (0, 1), (0, 2), (0, 3)]
+    );
+}
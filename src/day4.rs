@@ -1,3 +1,4 @@
+use crate::parse::{self, Line, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use lazy_static::lazy_static;
@@ -35,19 +36,19 @@ impl Passport {
         }
 
         if !matches!(self.birth_year,
-                Some(year) if year >= 1920 && year <= 2002)
+                Some(year) if (1920..=2002).contains(&year))
         {
             return false;
         }
 
         if !matches!(self.issue_year,
-                Some(year) if year >= 2010 && year <= 2020)
+                Some(year) if (2010..=2020).contains(&year))
         {
             return false;
         }
 
         if !matches!(self.expiration_year,
-                Some(year) if year >= 2020 && year <= 2030)
+                Some(year) if (2020..=2030).contains(&year))
         {
             return false;
         }
@@ -63,8 +64,8 @@ impl Passport {
                 };
                 let units = &captures[2];
                 match (value, units) {
-                    (value, "cm") if value >= 150 && value <= 193 => true,
-                    (value, "in") if value >= 59 && value <= 76 => true,
+                    (value, "cm") if (150..=193).contains(&value) => true,
+                    (value, "in") if (59..=76).contains(&value) => true,
                     _ => false,
                 }
             })
@@ -103,54 +104,54 @@ impl Passport {
         true
     }
 
-    fn parse_fields<'a, I>(&mut self, mut params: I)
-    where
-        I: Iterator<Item = &'a str>,
-    {
-        let key = if let Some(key) = params.next() {
-            key
-        } else {
-            return;
-        };
-
-        let value = if let Some(value) = params.next() {
-            value
-        } else {
-            return;
-        };
-
-        // TODO: Handle another param = failure?
+    fn parse_field(&mut self, line: &Line<'_>, field: &str) -> Result<(), ParseError> {
+        let mut params = field.split(':');
+        let key = params.next().unwrap();
+        let value = params
+            .next()
+            .ok_or_else(|| line.error(field, "a `key:value` pair"))?;
+        if let Some(extra) = params.next() {
+            return Err(line.error(extra, "a single `:` per field"));
+        }
 
         match key {
-            "byr" => self.birth_year = Some(value.parse().unwrap()),
-            "iyr" => self.issue_year = Some(value.parse().unwrap()),
-            "eyr" => self.expiration_year = Some(value.parse().unwrap()),
+            "byr" => self.birth_year = Some(line.parse(value, "a birth year")?),
+            "iyr" => self.issue_year = Some(line.parse(value, "an issue year")?),
+            "eyr" => self.expiration_year = Some(line.parse(value, "an expiration year")?),
             "hgt" => self.height = Some(value.into()),
             "hcl" => self.hair_color = Some(value.into()),
             "ecl" => self.eye_color = Some(value.into()),
             "pid" => self.passport_id = Some(value.into()),
             "cid" => self.country_id = Some(value.into()),
-            _ => {
-                println!("WTF is {}", key);
-            } // no-op, TODO: Log error?
+            _ => return Err(line.error(key, "one of byr, iyr, eyr, hgt, hcl, ecl, pid or cid")),
         }
+
+        Ok(())
     }
 }
 
 #[aoc_generator(day4)]
+pub fn try_input_generator(input: &str) -> Result<Vec<Passport>, ParseError> {
+    let mut passports = vec![];
+    let mut passport = None;
+    for line in parse::lines(4, input) {
+        if line.text().trim().is_empty() {
+            passports.extend(passport.take());
+            continue;
+        }
+
+        let current = passport.get_or_insert_with(Passport::default);
+        for field in line.text().split_ascii_whitespace() {
+            current.parse_field(&line, field)?;
+        }
+    }
+    passports.extend(passport);
+
+    Ok(passports)
+}
+
 pub fn input_generator(input: &str) -> Vec<Passport> {
-    input
-        .replace("\n", " ")
-        .split("  ")
-        .map(|line| {
-            let mut passport = <Passport as Default>::default();
-            for kv in line.split_ascii_whitespace() {
-                passport.parse_fields(kv.split(':'));
-            }
-
-            passport
-        })
-        .collect()
+    try_input_generator(input).unwrap()
 }
 
 #[aoc(day4, part1)]
@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 
@@ -14,19 +15,18 @@ impl Seat {
 }
 
 #[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> Vec<Seat> {
-    input
-        .lines()
+pub fn try_input_generator(input: &str) -> Result<Vec<Seat>, ParseError> {
+    parse::lines(5, input)
         .map(|line| {
             let mut left = 0;
             let mut right = 127;
-            let mut char_iter = line.chars();
+            let mut char_iter = line.text().char_indices();
             for _ in 0..7 {
-                let c = char_iter.next().unwrap();
+                let (i, c) = char_iter.next().ok_or_else(|| line.missing("`F` or `B`"))?;
                 match c {
                     'F' => right = (right - left) / 2 + left,
                     'B' => left = (right - left) / 2 + left + 1,
-                    _ => panic!("Unexpected char {}", c),
+                    _ => return Err(line.char_error(i, "`F` or `B`")),
                 }
             }
             assert_eq!(left, right);
@@ -35,21 +35,29 @@ pub fn input_generator(input: &str) -> Vec<Seat> {
             left = 0;
             right = 7;
             for _ in 0..3 {
-                let c = char_iter.next().unwrap();
+                let (i, c) = char_iter.next().ok_or_else(|| line.missing("`L` or `R`"))?;
                 match c {
                     'R' => left = (right - left) / 2 + left + 1,
                     'L' => right = (right - left) / 2 + left,
-                    _ => panic!("Unexpected char {}", c),
+                    _ => return Err(line.char_error(i, "`L` or `R`")),
                 }
             }
             assert_eq!(left, right);
             let column = left;
 
-            Seat { row, column }
+            if let Some((i, _)) = char_iter.next() {
+                return Err(line.error(&line.text()[i..], "end of line"));
+            }
+
+            Ok(Seat { row, column })
         })
         .collect()
 }
 
+pub fn input_generator(input: &str) -> Vec<Seat> {
+    try_input_generator(input).unwrap()
+}
+
 #[aoc(day5, part1)]
 pub fn solve_part1(input: &[Seat]) -> u32 {
     input.iter().map(|s| s.id()).max().unwrap()
@@ -57,7 +65,7 @@ pub fn solve_part1(input: &[Seat]) -> u32 {
 
 #[aoc(day5, part2)]
 pub fn solve_part2(input: &[Seat]) -> u32 {
-    let mut seats = input.iter().cloned().collect::<Vec<_>>();
+    let mut seats = input.to_vec();
     seats.sort_by_key(|s| s.id());
     for window in seats.windows(2) {
         if let [left, right] = window {
@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use std::collections::HashSet;
@@ -7,18 +8,36 @@ pub struct Group {
 }
 
 #[aoc_generator(day6)]
+pub fn try_input_generator(input: &str) -> Result<Vec<Group>, ParseError> {
+    let mut groups = vec![];
+    let mut group: Option<Group> = None;
+    for line in parse::lines(6, input) {
+        let answers = line.text().trim();
+        if answers.is_empty() {
+            groups.extend(group.take());
+            continue;
+        }
+
+        if let Some((i, c)) = answers
+            .char_indices()
+            .find(|(_, c)| !c.is_ascii_lowercase())
+        {
+            let answer = &answers[i..i + c.len_utf8()];
+            return Err(line.error(answer, "a question letter `a`-`z`"));
+        }
+
+        group
+            .get_or_insert_with(|| Group { set: vec![] })
+            .set
+            .push(answers.chars().collect());
+    }
+    groups.extend(group);
+
+    Ok(groups)
+}
+
 pub fn input_generator(input: &str) -> Vec<Group> {
-    input
-        .replace("\n", " ")
-        .split("  ")
-        .map(|line| Group {
-            set: line
-                .split(" ")
-                .map(|chunk| chunk.chars().collect::<Vec<_>>())
-                .filter(|v| v.len() > 0)
-                .collect::<Vec<_>>(),
-        })
-        .collect()
+    try_input_generator(input).unwrap()
 }
 
 #[aoc(day6, part1)]
@@ -36,7 +55,7 @@ pub fn solve_part2(input: &[Group]) -> usize {
         .map(|g| -> usize {
             let set = g.set.iter().flatten().collect::<HashSet<_>>();
             set.iter()
-                .map(|c| (g.set.len() > 0 && g.set.iter().all(|p| p.contains(c))) as usize)
+                .map(|c| (!g.set.is_empty() && g.set.iter().all(|p| p.contains(c))) as usize)
                 .sum()
         })
         .sum()
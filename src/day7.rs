@@ -1,3 +1,5 @@
+use crate::params::{Param, Params};
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 use regex::Regex;
@@ -16,7 +18,7 @@ pub struct Bag {
 }
 
 impl Bag {
-    fn contains_other(&self, bags: &Vec<Bag>, needle: &str) -> bool {
+    fn contains_other(&self, bags: &[Bag], needle: &str) -> bool {
         if self
             .holds
             .iter()
@@ -30,7 +32,7 @@ impl Bag {
             .any(|&Index { location, .. }| bags[location].contains_other(bags, needle))
     }
 
-    fn number_held(&self, bags: &Vec<Bag>) -> usize {
+    fn number_held(&self, bags: &[Bag]) -> usize {
         self.holds
             .iter()
             .map(|&Index { count, location }| count + count * bags[location].number_held(bags))
@@ -38,39 +40,44 @@ impl Bag {
     }
 }
 
+pub const PARAMS: &[Param] = &[Param::new::<String>(
+    "bag",
+    "shiny gold",
+    "the bag to look for",
+)];
+
 #[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> Vec<Bag> {
+pub fn try_input_generator(input: &str) -> Result<Vec<Bag>, ParseError> {
     let bag_re = Regex::new(r"^([0-9]+) ([a-z]+ [a-z]+) bags?$").unwrap();
     let mut lookup_map = HashMap::new();
     let mut bags = vec![];
-    for line in input.lines() {
-        let mut parts = line.split(" bags contain ");
+    for line in parse::lines(7, input) {
+        let mut parts = line.text().split(" bags contain ");
         let holder = String::from(parts.next().unwrap());
-        let rest = parts.next().unwrap();
-        assert!(parts.next().is_none());
-        let rest = rest.trim_end_matches('.');
+        let rest = parts
+            .next()
+            .ok_or_else(|| line.missing("` bags contain `"))?;
+        if let Some(extra) = parts.next() {
+            return Err(line.error(extra, "a single `bags contain` clause"));
+        }
+        let rest = rest.strip_suffix('.').ok_or_else(|| line.missing("`.`"))?;
         let mut holds = vec![];
-        for part in rest.split(", ") {
-            let captures = if let Some(captures) = bag_re.captures(part) {
-                captures
-            } else {
-                break; // no more matches
-            };
-
-            if let Some(val) = captures.get(1) {
+        if rest != "no other bags" {
+            for part in rest.split(", ") {
+                let captures = bag_re
+                    .captures(part)
+                    .ok_or_else(|| line.error(part, "`<count> <adjective> <color> bag(s)`"))?;
+                let count = captures.get(1).unwrap().as_str();
                 let held = String::from(&captures[2]);
-                let location = lookup_map
-                    .entry(held.clone())
-                    .or_insert_with(|| {
-                        bags.push(Bag {
-                            name: held,
-                            holds: vec![],
-                        });
-                        bags.len() - 1
-                    })
-                    .clone();
+                let location = *lookup_map.entry(held.clone()).or_insert_with(|| {
+                    bags.push(Bag {
+                        name: held,
+                        holds: vec![],
+                    });
+                    bags.len() - 1
+                });
                 holds.push(Index {
-                    count: val.as_str().parse().unwrap(),
+                    count: line.parse(count, "a bag count")?,
                     location,
                 });
             }
@@ -87,69 +94,37 @@ pub fn input_generator(input: &str) -> Vec<Bag> {
         }
     }
 
-    bags
+    Ok(bags)
+}
+
+pub fn input_generator(input: &str) -> Vec<Bag> {
+    try_input_generator(input).unwrap()
 }
 
 #[aoc(day7, part1)]
-pub fn solve_part1(input: &Vec<Bag>) -> usize {
+pub fn solve_part1(input: &[Bag]) -> usize {
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &[Bag], params: &Params) -> usize {
+    let needle = params.get::<String>("bag");
     input
         .iter()
-        .filter(|bag| bag.name != "shiny gold")
-        .map(|bag| bag.contains_other(input, "shiny gold") as usize)
+        .filter(|bag| bag.name != needle)
+        .map(|bag| bag.contains_other(input, &needle) as usize)
         .sum()
 }
 
 #[aoc(day7, part2)]
-pub fn solve_part2(input: &Vec<Bag>) -> usize {
+pub fn solve_part2(input: &[Bag]) -> usize {
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &[Bag], params: &Params) -> usize {
+    let needle = params.get::<String>("bag");
     input
         .iter()
-        .find(|bag| bag.name == "shiny gold")
+        .find(|bag| bag.name == needle)
         .map(|bag| bag.number_held(input))
         .unwrap_or(0)
 }
-
-#[test]
-fn test_input1() {
-    let content = "light red bags contain 1 bright white bag, 2 muted yellow bags.
-dark orange bags contain 3 bright white bags, 4 muted yellow bags.
-bright white bags contain 1 shiny gold bag.
-muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
-shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
-dark olive bags contain 3 faded blue bags, 4 dotted black bags.
-vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
-faded blue bags contain no other bags.
-dotted black bags contain no other bags.";
-    let input = input_generator(content);
-    let result = solve_part1(&input);
-    assert_eq!(result, 4);
-}
-
-#[test]
-fn test_input2() {
-    let content = "light red bags contain 1 bright white bag, 2 muted yellow bags.
-dark orange bags contain 3 bright white bags, 4 muted yellow bags.
-bright white bags contain 1 shiny gold bag.
-muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
-shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
-dark olive bags contain 3 faded blue bags, 4 dotted black bags.
-vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
-faded blue bags contain no other bags.
-dotted black bags contain no other bags.";
-    let input = input_generator(content);
-    let result = solve_part2(&input);
-    assert_eq!(result, 32);
-}
-
-#[test]
-fn test_input3() {
-    let content = "shiny gold bags contain 2 dark red bags.
-dark red bags contain 2 dark orange bags.
-dark orange bags contain 2 dark yellow bags.
-dark yellow bags contain 2 dark green bags.
-dark green bags contain 2 dark blue bags.
-dark blue bags contain 2 dark violet bags.
-dark violet bags contain no other bags.";
-    let input = input_generator(content);
-    let result = solve_part2(&input);
-    assert_eq!(result, 126);
-}
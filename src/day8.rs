@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 
@@ -7,7 +8,7 @@ pub enum Res {
     Pending,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum Instruction {
     Nop(isize),
     Acc(isize),
@@ -45,7 +46,7 @@ impl<'a> Processor<'a> {
 
     fn next(&mut self) -> Res {
         {
-            let ref mut accessed = self.instruction_counts[self.pc];
+            let accessed = &mut self.instruction_counts[self.pc];
             if *accessed {
                 return Res::Loop(self.acc);
             }
@@ -87,27 +88,28 @@ impl<'a> Processor<'a> {
 }
 
 #[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Vec<Instruction> {
-
-    input.lines().map(|line| {
-        let mut split = line.split(' ');
-        let op = split.next().expect("all lines should have one part");
-        match op {
-            "nop" => {
-                let diff = split.next().expect("nop takes arg").parse().unwrap();
-                Instruction::Nop(diff)
+pub fn try_input_generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse::lines(8, input)
+        .map(|line| {
+            let mut split = line.text().split(' ');
+            let op = split.next().unwrap();
+            let instruction = match op {
+                "nop" => Instruction::Nop(line.parse_next(split.next(), "a signed argument")?),
+                "acc" => Instruction::Acc(line.parse_next(split.next(), "a signed argument")?),
+                "jmp" => Instruction::Jmp(line.parse_next(split.next(), "a signed argument")?),
+                _ => return Err(line.error(op, "one of `nop`, `acc` or `jmp`")),
+            };
+            if let Some(extra) = split.next() {
+                return Err(line.error(extra, "end of line"));
             }
-            "acc" => {
-                let diff = split.next().expect("acc takes arg").parse().unwrap();
-                Instruction::Acc(diff)
-            },
-            "jmp" => {
-                let diff = split.next().expect("jmp takes arg").parse().unwrap();
-                Instruction::Jmp(diff)
-            },
-            _ => panic!("Unexpected variant {}", op),
-        }
-    }).collect()
+
+            Ok(instruction)
+        })
+        .collect()
+}
+
+pub fn input_generator(input: &str) -> Vec<Instruction> {
+    try_input_generator(input).unwrap()
 }
 
 #[aoc(day8, part1)]
@@ -135,7 +137,6 @@ pub fn solve_part2(input: &[Instruction]) -> isize {
             }
 
             if let Res::Complete(result) = result {
-                println!("{}", i);
                 return result;
             }
         }
@@ -145,33 +146,12 @@ pub fn solve_part2(input: &[Instruction]) -> isize {
 }
 
 #[test]
-fn test_input1() {
-    let content ="nop +0
-acc +1
-jmp +4
-acc +3
-jmp -3
-acc -99
-acc +1
-jmp -4
-acc +6";
-    let input = input_generator(content);
-    let result = solve_part1(&input);
-    assert_eq!(result, 5);
-}
-
-#[test]
-fn test_input2() {
-    let content ="nop +0
-acc +1
-jmp +4
-acc +3
-jmp -3
-acc -99
+fn test_parse_error() {
+    let content = "nop +0
 acc +1
-jmp -4
-acc +6";
-    let input = input_generator(content);
-    let result = solve_part2(&input);
-    assert_eq!(result, 8);
+jmp x4";
+    let err = try_input_generator(content).unwrap_err();
+    assert_eq!(err.line(), 3);
+    assert_eq!(err.column(), 5);
+    assert_eq!(err.text(), "x4");
 }
@@ -1,3 +1,5 @@
+use crate::params::{Param, Params};
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::aoc;
 use aoc_runner_derive::aoc_generator;
 
@@ -5,42 +7,48 @@ use aoc_runner_derive::aoc_generator;
 fn is_valid_range(input: &[usize], value: usize) -> bool {
     // Sort in O(n * log n).
     let input = {
-        let mut input = input.iter().copied().collect::<Vec<_>>();
+        let mut input = input.to_vec();
         input.sort();
         input
     };
 
-    // Sliding window calculations is O(n).
+    // Two pointers closing in from both ends is O(n).
     let mut left = 0;
-    let mut right = 1;
-    while right < input.len() {
-        let sum = input[left] + input[right];
+    let mut right = input.len();
+    while left + 1 < right {
+        let sum = input[left] + input[right - 1];
         if sum == value {
-            return true;
+            // Equal ends mean everything between is equal too.
+            return input[left] != input[right - 1];
         }
 
         if sum < value {
-            right += 1;
-        } else {
             left += 1;
-        }
-
-        if left == right {
-            right += 1;
+        } else {
+            right -= 1;
         }
     }
 
     false
 }
 
+pub const PARAMS: &[Param] = &[Param::at_least::<2>(
+    "preamble",
+    "25",
+    "how many previous numbers each number must be a sum of two of (at least 2)",
+)];
+
 #[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Vec<usize> {
-    input
-        .lines()
-        .map(|line| line.parse::<usize>().unwrap())
+pub fn try_input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse::lines(9, input)
+        .map(|line| line.parse(line.text(), "an unsigned integer"))
         .collect()
 }
 
+pub fn input_generator(input: &str) -> Vec<usize> {
+    try_input_generator(input).unwrap()
+}
+
 // Since preamble_size is always constant, Runtime is O(n) :)
 fn actually_solve_part1(input: &[usize], preamble_size: usize) -> usize {
     // Loop is O(n)
@@ -57,8 +65,11 @@ fn actually_solve_part1(input: &[usize], preamble_size: usize) -> usize {
 
 #[aoc(day9, part1)]
 pub fn solve_part1(input: &[usize]) -> usize {
-    const PREAMBLE_SIZE: usize = 25;
-    actually_solve_part1(input, PREAMBLE_SIZE)
+    solve_part1_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part1_with(input: &[usize], params: &Params) -> usize {
+    actually_solve_part1(input, params.get("preamble"))
 }
 
 // Runs in O(n)
@@ -72,47 +83,33 @@ fn actually_solve_part2(input: &[usize], preamble_size: usize) -> usize {
         }
     }
 
-    // Sliding window runs in O(n).
+    // Sliding window runs in O(n). The window is `input[left..=right]` and
+    // needs at least two numbers.
     let mut left = 0;
-    let mut right = 1;
-    let mut sum: usize = input[left..=right].iter().sum();
-    while right < input.len() {
-        if sum == expected {
-            let (min, max) = input[left..=right].iter().fold((std::usize::MAX, 0), |(min, max), &val| {
-                if val < min {
-                    (val, max)
-                } else if val > max {
-                    (min, val)
-                } else {
-                    (min, max)
-                }
-            });
-            return min + max;
-        }
-
-        if sum > expected {
+    let mut sum = 0;
+    for right in 0..input.len() {
+        sum += input[right];
+        while sum > expected && left < right {
             sum -= input[left];
             left += 1;
-        } else {
-            right += 1;
-            sum += input[right];
         }
 
-        if right - left < 2 {
-            right += 1;
-            if right < input.len() {
-                sum += input[right];
-            }
+        if sum == expected && left < right {
+            let window = &input[left..=right];
+            return window.iter().min().unwrap() + window.iter().max().unwrap();
         }
     }
 
-    return expected;
+    panic!("Range not found");
 }
 
 #[aoc(day9, part2)]
 pub fn solve_part2(input: &[usize]) -> usize {
-    const PREAMBLE_SIZE: usize = 25;
-    actually_solve_part2(input, PREAMBLE_SIZE)
+    solve_part2_with(input, &Params::defaults(PARAMS))
+}
+
+pub fn solve_part2_with(input: &[usize], params: &Params) -> usize {
+    actually_solve_part2(input, params.get("preamble"))
 }
 
 #[test]
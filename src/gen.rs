@@ -0,0 +1,1385 @@
+//! Synthetic puzzle inputs with planted answers, for stress tests and
+//! benchmarks that cannot use the real inputs.
+//!
+//! Every generator builds its input around answers it chooses up front (or
+//! derives from the construction), so they can be checked against the
+//! solvers. Output depends only on the seed and size.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// A small, fast SplitMix64 generator, so seeded output is stable without
+/// pulling in a dependency.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `low..=high`. The modulo bias is irrelevant for inputs.
+    pub fn between(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low <= high, "empty range {}..={}", low, high);
+        match (high - low).checked_add(1) {
+            Some(span) => low + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    /// An index below `len`.
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "cannot pick from nothing");
+        self.between(0, len as u64 - 1) as usize
+    }
+
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.between(1, denominator) <= numerator
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// A generated input along with the answers planted in it.
+pub struct Generated {
+    pub input: String,
+    pub answers: [String; 2],
+}
+
+/// Builds an input of roughly `size` entries (lines, groups or buses,
+/// depending on the day).
+pub struct Generator {
+    day: u32,
+    generate: fn(&mut Rng, usize) -> Generated,
+}
+
+impl Generator {
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    pub fn generate(&self, seed: u64, size: usize) -> Generated {
+        (self.generate)(&mut Rng::new(seed), size)
+    }
+}
+
+/// Every day that has a generator, in order.
+pub static GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        generate: day1,
+    },
+    Generator {
+        day: 2,
+        generate: day2,
+    },
+    Generator {
+        day: 3,
+        generate: day3,
+    },
+    Generator {
+        day: 4,
+        generate: day4,
+    },
+    Generator {
+        day: 5,
+        generate: day5,
+    },
+    Generator {
+        day: 6,
+        generate: day6,
+    },
+    Generator {
+        day: 7,
+        generate: day7,
+    },
+    Generator {
+        day: 8,
+        generate: day8,
+    },
+    Generator {
+        day: 9,
+        generate: day9,
+    },
+    Generator {
+        day: 10,
+        generate: day10,
+    },
+    Generator {
+        day: 11,
+        generate: day11,
+    },
+    Generator {
+        day: 12,
+        generate: day12,
+    },
+    Generator {
+        day: 13,
+        generate: day13,
+    },
+    Generator {
+        day: 14,
+        generate: day14,
+    },
+    Generator {
+        day: 15,
+        generate: day15,
+    },
+    Generator {
+        day: 16,
+        generate: day16,
+    },
+    Generator {
+        day: 17,
+        generate: day17,
+    },
+    Generator {
+        day: 18,
+        generate: day18,
+    },
+    Generator {
+        day: 19,
+        generate: day19,
+    },
+];
+
+pub fn find(day: u32) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.day == day)
+}
+
+fn lines(items: impl IntoIterator<Item = impl ToString>) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string() + "\n")
+        .collect()
+}
+
+/// An expense report with exactly one pair and one triple summing to 2020.
+fn day1(rng: &mut Rng, size: usize) -> Generated {
+    const TARGET: u64 = 2020;
+
+    fn count_pairs(entries: &HashSet<u64>) -> usize {
+        entries
+            .iter()
+            .filter(|&&a| a < TARGET - a && entries.contains(&(TARGET - a)))
+            .count()
+    }
+
+    fn count_triples(entries: &HashSet<u64>) -> usize {
+        let mut count = 0;
+        for &a in entries {
+            for &b in entries {
+                if a < b && a + b < TARGET {
+                    let c = TARGET - a - b;
+                    if b < c && entries.contains(&c) {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    // Whether adding `v` would complete another pair or triple.
+    fn completes(entries: &HashSet<u64>, v: u64) -> bool {
+        entries.contains(&(TARGET - v))
+            || entries.iter().any(|&a| {
+                a + v < TARGET && a != TARGET - v - a && entries.contains(&(TARGET - v - a))
+            })
+    }
+
+    let (pair, triple, mut entries) = loop {
+        let x = rng.between(1, TARGET / 2 - 1);
+        let a = rng.between(1, TARGET / 3 - 1);
+        let b = rng.between(a + 1, (TARGET - a - 1) / 2);
+        let c = TARGET - a - b;
+        let entries = [x, TARGET - x, a, b, c]
+            .iter()
+            .copied()
+            .collect::<HashSet<_>>();
+        if entries.len() == 5 && count_pairs(&entries) == 1 && count_triples(&entries) == 1 {
+            break ((x, TARGET - x), (a, b, c), entries);
+        }
+    };
+
+    // Filler above half the target can only pair up with the planted values,
+    // which `completes` rules out.
+    let filler = (TARGET / 2 + 1..TARGET).count();
+    let mut attempts = 0;
+    while entries.len() < size.min(filler / 2) && attempts < 100 * size {
+        attempts += 1;
+        let v = rng.between(TARGET / 2 + 1, TARGET - 1);
+        if !entries.contains(&v) && !completes(&entries, v) {
+            entries.insert(v);
+        }
+    }
+
+    let mut entries = entries.into_iter().collect::<Vec<_>>();
+    entries.sort_unstable();
+    rng.shuffle(&mut entries);
+    Generated {
+        input: lines(entries),
+        answers: [
+            (pair.0 * pair.1).to_string(),
+            (triple.0 * triple.1 * triple.2).to_string(),
+        ],
+    }
+}
+
+/// Passwords where each line's validity under both policies is chosen first.
+fn day2(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut valid = [0; 2];
+    for _ in 0..size.max(1) {
+        let (count_valid, position_valid) = (rng.chance(1, 2), rng.chance(1, 2));
+        let line = loop {
+            let lower = rng.between(1, 8) as usize;
+            let upper = rng.between(lower as u64 + 1, lower as u64 + 10) as usize;
+            let len = rng.between(upper as u64, upper as u64 + 6) as usize;
+            let letter = (b'a' + rng.between(0, 25) as u8) as char;
+
+            // How many times `letter` appears at `lower` and `upper`.
+            let at_positions = match (position_valid, rng.chance(1, 2)) {
+                (true, _) => 1,
+                (false, true) => 0,
+                (false, false) => 2,
+            };
+            let counts = (at_positions..=at_positions + len - 2)
+                .filter(|count| (lower..=upper).contains(count) == count_valid)
+                .collect::<Vec<_>>();
+            if counts.is_empty() {
+                continue;
+            }
+            let count = counts[rng.index(counts.len())];
+
+            let others = (1..=len)
+                .filter(|&i| i != lower && i != upper)
+                .collect::<Vec<_>>();
+            let mut chosen = others.clone();
+            rng.shuffle(&mut chosen);
+            chosen.truncate(count - at_positions);
+            match at_positions {
+                1 => chosen.push(if rng.chance(1, 2) { lower } else { upper }),
+                2 => chosen.extend(&[lower, upper]),
+                _ => {}
+            }
+
+            let password = (1..=len)
+                .map(|i| {
+                    if chosen.contains(&i) {
+                        letter
+                    } else {
+                        let other = rng.between(0, 24) as u8;
+                        let other = (b'a' + other) as char;
+                        if other >= letter {
+                            (other as u8 + 1) as char
+                        } else {
+                            other
+                        }
+                    }
+                })
+                .collect::<String>();
+            break format!("{}-{} {}: {}", lower, upper, letter, password);
+        };
+
+        valid[0] += count_valid as usize;
+        valid[1] += position_valid as usize;
+        writeln!(input, "{}", line).unwrap();
+    }
+
+    Generated {
+        input,
+        answers: [valid[0].to_string(), valid[1].to_string()],
+    }
+}
+
+/// A forest of randomly placed trees, counted along the five slopes as the
+/// map is drawn.
+fn day3(rng: &mut Rng, size: usize) -> Generated {
+    const SLOPES: [(usize, usize); 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+    let width = rng.between(5, 40) as usize;
+    let density = rng.between(1, 5);
+    let map = (0..size.max(2))
+        .map(|_| {
+            (0..width)
+                .map(|_| rng.chance(density, 10))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let trees = SLOPES
+        .iter()
+        .map(|&(right, down)| {
+            map.iter()
+                .step_by(down)
+                .enumerate()
+                .filter(|&(step, row)| row[step * right % width])
+                .count()
+        })
+        .collect::<Vec<_>>();
+
+    Generated {
+        input: lines(map.iter().map(|row| {
+            row.iter()
+                .map(|&tree| if tree { '#' } else { '.' })
+                .collect::<String>()
+        })),
+        answers: [
+            trees[1].to_string(),
+            trees.iter().product::<usize>().to_string(),
+        ],
+    }
+}
+
+/// Passports that are each chosen up front to be missing a required field,
+/// to break one field's rule, or to be valid.
+fn day4(rng: &mut Rng, size: usize) -> Generated {
+    const EYES: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+    /// A value from either range, for fields that are invalid on both sides.
+    fn either(rng: &mut Rng, low: (u64, u64), high: (u64, u64)) -> u64 {
+        if rng.chance(1, 2) {
+            rng.between(low.0, low.1)
+        } else {
+            rng.between(high.0, high.1)
+        }
+    }
+
+    fn invalid(rng: &mut Rng, field: &str) -> String {
+        match field {
+            "byr" => either(rng, (1900, 1919), (2003, 2030)).to_string(),
+            "iyr" => either(rng, (2000, 2009), (2021, 2030)).to_string(),
+            "eyr" => either(rng, (2010, 2019), (2031, 2040)).to_string(),
+            "hgt" => match rng.between(0, 2) {
+                0 => format!("{}cm", either(rng, (100, 149), (194, 250))),
+                1 => format!("{}in", either(rng, (40, 58), (77, 99))),
+                _ => rng.between(59, 193).to_string(),
+            },
+            "hcl" => match rng.between(0, 2) {
+                0 => format!("{:06x}", rng.between(0, 0xff_ffff)),
+                1 => format!("#{:05x}", rng.between(0, 0xf_ffff)),
+                _ => format!("#{:07x}", rng.between(0, 0xfff_ffff)),
+            },
+            "ecl" => ["red", "blk", "amber", "xyz"][rng.index(4)].into(),
+            _ => match rng.chance(1, 2) {
+                true => format!("{:08}", rng.between(0, 99_999_999)),
+                false => format!("{:010}", rng.between(0, 9_999_999_999)),
+            },
+        }
+    }
+
+    let mut passports = vec![];
+    let (mut present, mut valid) = (0, 0);
+    for _ in 0..size.max(1) {
+        let height = if rng.chance(1, 2) {
+            format!("{}cm", rng.between(150, 193))
+        } else {
+            format!("{}in", rng.between(59, 76))
+        };
+        let mut fields = vec![
+            ("byr", rng.between(1920, 2002).to_string()),
+            ("iyr", rng.between(2010, 2020).to_string()),
+            ("eyr", rng.between(2020, 2030).to_string()),
+            ("hgt", height),
+            ("hcl", format!("#{:06x}", rng.between(0, 0xff_ffff))),
+            ("ecl", EYES[rng.index(EYES.len())].into()),
+            ("pid", format!("{:09}", rng.between(0, 999_999_999))),
+        ];
+        match rng.between(0, 2) {
+            0 => {
+                fields.remove(rng.index(fields.len()));
+            }
+            1 => {
+                let field = &mut fields[rng.index(7)];
+                field.1 = invalid(rng, field.0);
+                present += 1;
+            }
+            _ => {
+                present += 1;
+                valid += 1;
+            }
+        }
+        if rng.chance(1, 2) {
+            fields.push(("cid", rng.between(1, 999).to_string()));
+        }
+        rng.shuffle(&mut fields);
+
+        let mut passport = String::new();
+        for (i, (field, value)) in fields.iter().enumerate() {
+            if i > 0 {
+                passport.push(if rng.chance(1, 3) { '\n' } else { ' ' });
+            }
+            write!(passport, "{}:{}", field, value).unwrap();
+        }
+        passports.push(passport + "\n");
+    }
+
+    Generated {
+        input: passports.join("\n"),
+        answers: [present.to_string(), valid.to_string()],
+    }
+}
+
+/// A contiguous block of boarding passes with a single seat missing.
+fn day5(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.clamp(3, 1000) as u64;
+    let first = rng.between(1, 1023 - count);
+    let last = first + count;
+    let missing = rng.between(first + 1, last - 1);
+
+    let mut passes = (first..=last)
+        .filter(|&id| id != missing)
+        .map(|id| {
+            let row = (0..7)
+                .rev()
+                .map(|bit| if id >> 3 >> bit & 1 == 1 { 'B' } else { 'F' });
+            let column = (0..3)
+                .rev()
+                .map(|bit| if id >> bit & 1 == 1 { 'R' } else { 'L' });
+            row.chain(column).collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut passes);
+
+    Generated {
+        input: lines(passes),
+        answers: [last.to_string(), missing.to_string()],
+    }
+}
+
+/// Groups whose questions anyone and everyone answered are chosen first.
+fn day6(rng: &mut Rng, size: usize) -> Generated {
+    let mut groups = vec![];
+    let (mut anyone, mut everyone) = (0, 0);
+    for _ in 0..size.max(1) {
+        let group = loop {
+            let people = rng.between(1, 5) as usize;
+            let mut questions = (b'a'..=b'z').map(char::from).collect::<Vec<_>>();
+            rng.shuffle(&mut questions);
+            questions.truncate(rng.between(1, 26) as usize);
+            let shared = if people == 1 {
+                questions.len()
+            } else {
+                rng.between(0, questions.len() as u64) as usize
+            };
+
+            // Everyone answers the shared questions; each other question is
+            // answered by some, but not all, of the group.
+            let mut answers = vec![questions[..shared].to_vec(); people];
+            for &question in &questions[shared..] {
+                let mut answered = (0..people).collect::<Vec<_>>();
+                rng.shuffle(&mut answered);
+                answered.truncate(rng.between(1, people as u64 - 1) as usize);
+                for person in answered {
+                    answers[person].push(question);
+                }
+            }
+            if answers.iter().any(Vec::is_empty) {
+                continue;
+            }
+
+            anyone += questions.len();
+            everyone += shared;
+            break answers;
+        };
+
+        let group = group
+            .into_iter()
+            .map(|mut answers| {
+                rng.shuffle(&mut answers);
+                answers.into_iter().collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        groups.push(lines(group));
+    }
+
+    Generated {
+        input: groups.join("\n"),
+        answers: [anyone.to_string(), everyone.to_string()],
+    }
+}
+
+/// Bags in layers, each holding bags from the layer below, with shiny gold in
+/// the middle. The answers are counted up from the bottom layer.
+fn day7(rng: &mut Rng, size: usize) -> Generated {
+    const ADJECTIVES: [&str; 16] = [
+        "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+        "muted", "pale", "plaid", "posh", "shiny", "vibrant",
+    ];
+    const COLORS: [&str; 16] = [
+        "aqua", "beige", "black", "blue", "bronze", "coral", "crimson", "gold", "gray", "green",
+        "lime", "olive", "plum", "red", "teal", "white",
+    ];
+    const LAYERS: usize = 5;
+    const GOLD: &str = "shiny gold";
+
+    let mut names = ADJECTIVES
+        .iter()
+        .flat_map(|adjective| {
+            COLORS
+                .iter()
+                .map(move |color| format!("{} {}", adjective, color))
+        })
+        .filter(|name| name != GOLD)
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut names);
+    names.truncate(size.clamp(1, names.len()));
+    let mut layers = vec![vec![]; LAYERS];
+    layers[LAYERS / 2].push(GOLD.to_string());
+    for name in names {
+        layers[rng.index(LAYERS)].push(name);
+    }
+
+    // Bags in the lowest layer hold nothing, and each other bag holds a few
+    // bags from the layer below it.
+    let mut rules = vec![];
+    let mut holds_gold = HashSet::new();
+    let mut held = HashMap::<String, u64>::new();
+    for depth in (0..LAYERS).rev() {
+        for name in &layers[depth] {
+            let below = layers.get(depth + 1).map_or(&[][..], Vec::as_slice);
+            let mut inner = below.to_vec();
+            rng.shuffle(&mut inner);
+            inner.truncate(rng.between(0, 3) as usize);
+
+            let mut contents = vec![];
+            let mut total = 0;
+            for bag in inner {
+                let count = rng.between(1, 5);
+                total += count * (1 + held[&bag]);
+                if bag == GOLD || holds_gold.contains(&bag) {
+                    holds_gold.insert(name.clone());
+                }
+                let plural = if count == 1 { "" } else { "s" };
+                contents.push(format!("{} {} bag{}", count, bag, plural));
+            }
+            held.insert(name.clone(), total);
+
+            if contents.is_empty() {
+                contents.push("no other bags".into());
+            }
+            rules.push(format!("{} bags contain {}.", name, contents.join(", ")));
+        }
+    }
+    rng.shuffle(&mut rules);
+
+    Generated {
+        input: lines(rules),
+        answers: [holds_gold.len().to_string(), held[GOLD].to_string()],
+    }
+}
+
+/// A boot program where flipping the one backwards `jmp` at the end of the
+/// executed prefix is the only way to terminate.
+fn day8(rng: &mut Rng, size: usize) -> Generated {
+    fn acc(rng: &mut Rng) -> i64 {
+        rng.between(0, 100) as i64 - 50
+    }
+
+    // Appends executed instructions, skipping over dead `acc`s with forward
+    // jumps, and returns their sum along with the executed indices. In the
+    // prefix, `nop`s only point backwards so flipping them cannot skip the
+    // corrupted instruction.
+    fn block(
+        rng: &mut Rng,
+        program: &mut Vec<String>,
+        len: usize,
+        prefix: bool,
+    ) -> (i64, Vec<usize>) {
+        let mut sum = 0;
+        let mut executed = vec![];
+        while program.len() < len {
+            executed.push(program.len());
+            match rng.between(0, 5) {
+                0..=2 => {
+                    let value = acc(rng);
+                    sum += value;
+                    program.push(format!("acc {:+}", value));
+                }
+                3 | 4 => {
+                    let value = if prefix {
+                        -(rng.between(0, program.len() as u64) as i64)
+                    } else {
+                        rng.between(0, 10) as i64 - 5
+                    };
+                    program.push(format!("nop {:+}", value));
+                }
+                _ => {
+                    let skip = rng.between(1, 4);
+                    program.push(format!("jmp +{}", skip + 1));
+                    for _ in 0..skip {
+                        let value = acc(rng);
+                        program.push(format!("acc {:+}", value));
+                    }
+                }
+            }
+        }
+        (sum, executed)
+    }
+
+    let len = size.max(4);
+    let mut program = vec![];
+    let (before, executed) = block(rng, &mut program, len / 2, true);
+    let target = executed[rng.index(executed.len())];
+    program.push(format!("jmp {:+}", target as i64 - program.len() as i64));
+    let (after, _) = block(rng, &mut program, len, false);
+
+    Generated {
+        input: lines(program),
+        answers: [before.to_string(), (before + after).to_string()],
+    }
+}
+
+/// A sequence where every number after the preamble is the sum of two of the
+/// 25 before it, except one that is instead the sum of a planted run of
+/// earlier numbers.
+fn day9(rng: &mut Rng, size: usize) -> Generated {
+    const PREAMBLE: usize = 25;
+
+    fn is_pair_sum(window: &[u64], value: u64) -> bool {
+        window
+            .iter()
+            .enumerate()
+            .any(|(i, &a)| window[i + 1..].iter().any(|&b| a != b && a + b == value))
+    }
+
+    /// How many runs of at least two numbers sum to `value`.
+    fn runs(numbers: &[u64], value: u64) -> usize {
+        let mut count = 0;
+        for start in 0..numbers.len() {
+            let mut sum = numbers[start];
+            for &number in &numbers[start + 1..] {
+                sum += number;
+                if sum >= value {
+                    count += (sum == value) as usize;
+                    break;
+                }
+            }
+        }
+        count
+    }
+
+    // Summing the smallest numbers in the window keeps a thousand numbers
+    // within 64 bits.
+    let len = PREAMBLE + size.clamp(2, 1000);
+    loop {
+        let mut numbers = (1..=60).collect::<Vec<u64>>();
+        rng.shuffle(&mut numbers);
+        numbers.truncate(PREAMBLE);
+
+        let invalid = rng.between(((PREAMBLE + len) / 2) as u64, len as u64 - 1) as usize;
+        let start = rng.index(invalid - 2);
+        let end = rng.between(start as u64 + 3, invalid.min(start + 17) as u64) as usize;
+        while numbers.len() < len {
+            let next = if numbers.len() == invalid {
+                numbers[start..end].iter().sum()
+            } else {
+                let mut window = numbers[numbers.len() - PREAMBLE..].to_vec();
+                window.sort_unstable();
+                window.dedup();
+                window.truncate(6);
+                rng.shuffle(&mut window);
+                window[0] + window[1]
+            };
+            numbers.push(next);
+        }
+
+        // The planted run has to be the only one, and the number it sums to
+        // can't also be a sum of two.
+        let value = numbers[invalid];
+        if is_pair_sum(&numbers[invalid - PREAMBLE..invalid], value) || runs(&numbers, value) != 1 {
+            continue;
+        }
+
+        let run = &numbers[start..end];
+        break Generated {
+            input: lines(&numbers),
+            answers: [
+                value.to_string(),
+                (run.iter().min().unwrap() + run.iter().max().unwrap()).to_string(),
+            ],
+        };
+    }
+}
+
+/// Adapters that differ by one or three jolts, in runs of up to four ones,
+/// so the number of arrangements is a product of tribonacci numbers.
+fn day10(rng: &mut Rng, size: usize) -> Generated {
+    const ARRANGEMENTS: [u128; 5] = [1, 1, 2, 4, 7];
+
+    let (mut ones, mut threes, mut arrangements) = (0, 1, 1);
+    let mut adapters = vec![];
+    let mut joltage = 0;
+    while adapters.len() < size.max(2) {
+        let run = rng.between(0, 4) as usize;
+        for _ in 0..run {
+            joltage += 1;
+            adapters.push(joltage);
+        }
+        joltage += 3;
+        adapters.push(joltage);
+        ones += run;
+        threes += 1;
+        arrangements *= ARRANGEMENTS[run];
+    }
+    rng.shuffle(&mut adapters);
+
+    Generated {
+        input: lines(adapters),
+        answers: [(ones * threes).to_string(), arrangements.to_string()],
+    }
+}
+
+/// An empty seat layout with floor scattered through it. Where people end up
+/// can't be chosen up front, so the answers come from playing both sets of
+/// rules out on the layout.
+fn day11(rng: &mut Rng, size: usize) -> Generated {
+    /// Runs the rules until nothing changes and counts the occupied seats.
+    /// `None` is floor, and `far` looks past it to the first seat. Some
+    /// layouts never settle, and those give `None` back.
+    fn settle(seats: &[Vec<Option<bool>>], tolerance: usize, far: bool) -> Option<usize> {
+        let (height, width) = (seats.len() as isize, seats[0].len() as isize);
+        let mut seats = seats.to_vec();
+        let mut previous = Vec::new();
+        for _ in 0..1000 {
+            let mut next = seats.clone();
+            for y in 0..height {
+                for x in 0..width {
+                    let occupied = match seats[y as usize][x as usize] {
+                        Some(occupied) => occupied,
+                        None => continue,
+                    };
+
+                    let mut seen = 0;
+                    for &(dx, dy) in &crate::grid::ALL_DIRECTIONS {
+                        let (mut x, mut y) = (x + dx, y + dy);
+                        while (0..width).contains(&x) && (0..height).contains(&y) {
+                            if let Some(occupied) = seats[y as usize][x as usize] {
+                                seen += occupied as usize;
+                                break;
+                            }
+                            if !far {
+                                break;
+                            }
+                            x += dx;
+                            y += dy;
+                        }
+                    }
+                    next[y as usize][x as usize] = Some(if occupied {
+                        seen < tolerance
+                    } else {
+                        seen == 0
+                    });
+                }
+            }
+
+            if next == seats {
+                return Some(
+                    seats
+                        .iter()
+                        .flatten()
+                        .filter(|&&seat| seat == Some(true))
+                        .count(),
+                );
+            }
+            if next == previous {
+                return None;
+            }
+            previous = std::mem::replace(&mut seats, next);
+        }
+        None
+    }
+
+    loop {
+        let width = rng.between(5, 40) as usize;
+        let floor = rng.between(1, 4);
+        let seats = (0..size.max(1))
+            .map(|_| {
+                (0..width)
+                    .map(|_| Some(false).filter(|_| !rng.chance(floor, 10)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // The solver loops until the seats settle, so it must never see a
+        // layout that flips back and forth.
+        let (part1, part2) = match (settle(&seats, 4, false), settle(&seats, 5, true)) {
+            (Some(part1), Some(part2)) => (part1, part2),
+            _ => continue,
+        };
+        return Generated {
+            input: lines(seats.iter().map(|row| {
+                row.iter()
+                    .map(|seat| if seat.is_some() { 'L' } else { '.' })
+                    .collect::<String>()
+            })),
+            answers: [part1.to_string(), part2.to_string()],
+        };
+    }
+}
+
+/// Navigation instructions, following the ship both ways as each one is
+/// chosen. Instructions that would take the solver out of 32-bit range are
+/// chosen again.
+fn day12(rng: &mut Rng, size: usize) -> Generated {
+    const SHIP_LIMIT: i64 = 1_000_000_000;
+    const WAYPOINT_LIMIT: i64 = 1_000_000;
+
+    let mut instructions = vec![];
+    let (mut ship, mut heading) = ((0i64, 0i64), (1i64, 0i64));
+    let (mut guided, mut waypoint) = ((0i64, 0i64), (10i64, 1i64));
+    while instructions.len() < size.max(1) {
+        let action = b"NESWLRF"[rng.index(7)] as char;
+        let value = match action {
+            'L' | 'R' => 90 * rng.between(1, 3) as i64,
+            _ => rng.between(1, 100) as i64,
+        };
+
+        let (mut next_ship, mut next_heading) = (ship, heading);
+        let (mut next_guided, mut next_waypoint) = (guided, waypoint);
+        match action {
+            'N' | 'E' | 'S' | 'W' => {
+                let (dx, dy) = match action {
+                    'N' => (0, 1),
+                    'E' => (1, 0),
+                    'S' => (0, -1),
+                    _ => (-1, 0),
+                };
+                next_ship = (ship.0 + dx * value, ship.1 + dy * value);
+                next_waypoint = (waypoint.0 + dx * value, waypoint.1 + dy * value);
+            }
+            'L' | 'R' => {
+                for _ in 0..value / 90 {
+                    let turn = |(x, y): (i64, i64)| if action == 'L' { (-y, x) } else { (y, -x) };
+                    next_heading = turn(next_heading);
+                    next_waypoint = turn(next_waypoint);
+                }
+            }
+            _ => {
+                next_ship = (ship.0 + heading.0 * value, ship.1 + heading.1 * value);
+                next_guided = (guided.0 + waypoint.0 * value, guided.1 + waypoint.1 * value);
+            }
+        }
+
+        let distance = |(x, y): (i64, i64)| x.abs() + y.abs();
+        if distance(next_ship).max(distance(next_guided)) > SHIP_LIMIT
+            || distance(next_waypoint) > WAYPOINT_LIMIT
+        {
+            continue;
+        }
+        ship = next_ship;
+        heading = next_heading;
+        guided = next_guided;
+        waypoint = next_waypoint;
+        instructions.push(format!("{}{}", action, value));
+    }
+
+    Generated {
+        input: lines(instructions),
+        answers: [
+            (ship.0.abs() + ship.1.abs()).to_string(),
+            (guided.0.abs() + guided.1.abs()).to_string(),
+        ],
+    }
+}
+
+/// A schedule of distinct prime buses whose offsets are placed so that a
+/// chosen timestamp is the answer to part 2.
+fn day13(rng: &mut Rng, size: usize) -> Generated {
+    const PRIMES: [u64; 22] = [
+        7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    ];
+
+    let mut primes = PRIMES.to_vec();
+    rng.shuffle(&mut primes);
+    primes.truncate(size.clamp(2, 9));
+    let modulus = primes.iter().map(|&p| p as u128).product::<u128>();
+    let timestamp =
+        primes[0] as u128 * rng.between(1, (modulus / primes[0] as u128) as u64 - 1) as u128;
+
+    // The first bus leaves at `timestamp`, and each later one at the next
+    // offset where it divides `timestamp + offset`.
+    let mut schedule = vec![primes[0].to_string()];
+    for &bus in &primes[1..] {
+        let bus_u128 = bus as u128;
+        let mut offset = schedule.len() as u128;
+        while !(timestamp + offset).is_multiple_of(bus_u128) {
+            offset += 1;
+        }
+        while (schedule.len() as u128) < offset {
+            schedule.push("x".into());
+        }
+        schedule.push(bus.to_string());
+    }
+
+    let (earliest, answer) = loop {
+        let earliest = rng.between(100_000, 1_000_000);
+        let mut waits = primes
+            .iter()
+            .map(|&bus| (bus - earliest % bus, bus))
+            .collect::<Vec<_>>();
+        waits.sort_unstable();
+        if waits[0].0 != waits[1].0 {
+            break (earliest, waits[0].0 * waits[0].1);
+        }
+    };
+
+    Generated {
+        input: format!("{}\n{}\n", earliest, schedule.join(",")),
+        answers: [answer.to_string(), timestamp.to_string()],
+    }
+}
+
+/// Masks and writes, with memory tracked under both decoders as each write is
+/// chosen. Masks float at most nine bits, as in the real inputs.
+fn day14(rng: &mut Rng, size: usize) -> Generated {
+    let mut program = vec![];
+    let (mut ones, mut floating) = (0, 0);
+    let mut values = HashMap::new();
+    let mut addresses = HashMap::new();
+    for i in 0..size.max(1) {
+        if i == 0 || rng.chance(1, 4) {
+            let mut bits = (0..36)
+                .map(|_| if rng.chance(1, 2) { '1' } else { '0' })
+                .collect::<Vec<_>>();
+            let mut positions = (0..36).collect::<Vec<_>>();
+            rng.shuffle(&mut positions);
+            for &position in &positions[..rng.between(0, 9) as usize] {
+                bits[position] = 'X';
+            }
+
+            let mask = |bit| {
+                bits.iter()
+                    .fold(0u64, |mask, &c| mask << 1 | (c == bit) as u64)
+            };
+            ones = mask('1');
+            floating = mask('X');
+            program.push(format!("mask = {}", bits.iter().collect::<String>()));
+        }
+
+        let address = rng.between(0, 65535);
+        let value = rng.between(0, (1 << 36) - 1);
+        values.insert(address, value & (ones | floating) | ones);
+        let base = (address | ones) & !floating;
+        let mut subset = floating;
+        loop {
+            addresses.insert(base | subset, value);
+            if subset == 0 {
+                break;
+            }
+            subset = (subset - 1) & floating;
+        }
+        program.push(format!("mem[{}] = {}", address, value));
+    }
+
+    Generated {
+        input: lines(program),
+        answers: [
+            values.values().sum::<u64>().to_string(),
+            addresses.values().sum::<u64>().to_string(),
+        ],
+    }
+}
+
+/// A few distinct starting numbers. Nothing short of playing the game says
+/// what is spoken, so the answers come from playing it out, and `size` is
+/// ignored.
+fn day15(rng: &mut Rng, _size: usize) -> Generated {
+    fn play(starting: &[u32], turns: usize) -> u32 {
+        const UNSEEN: u32 = u32::MAX;
+        let mut last_seen = vec![UNSEEN; turns];
+        for (turn, &number) in starting[..starting.len() - 1].iter().enumerate() {
+            last_seen[number as usize] = turn as u32;
+        }
+
+        let mut spoken = starting[starting.len() - 1];
+        for turn in starting.len() - 1..turns - 1 {
+            let seen = std::mem::replace(&mut last_seen[spoken as usize], turn as u32);
+            spoken = if seen == UNSEEN {
+                0
+            } else {
+                turn as u32 - seen
+            };
+        }
+        spoken
+    }
+
+    let mut starting = (0..20).collect::<Vec<_>>();
+    rng.shuffle(&mut starting);
+    starting.truncate(rng.between(3, 7) as usize);
+
+    Generated {
+        input: starting
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+            + "\n",
+        answers: [
+            play(&starting, 2020).to_string(),
+            play(&starting, 30_000_000).to_string(),
+        ],
+    }
+}
+
+/// Ticket fields that accept a staircase of values: splitting the valid values
+/// into bands, the field ranked `k` accepts band `k` and everything above it,
+/// and each column holds values from its field's band up. Only one field fits
+/// the column with the top band, then only one of the rest, and so on.
+fn day16(rng: &mut Rng, size: usize) -> Generated {
+    const FIELDS: [&str; 20] = [
+        "departure location",
+        "departure station",
+        "departure platform",
+        "departure track",
+        "departure date",
+        "departure time",
+        "arrival location",
+        "arrival station",
+        "arrival platform",
+        "arrival track",
+        "class",
+        "duration",
+        "price",
+        "route",
+        "row",
+        "seat",
+        "train",
+        "type",
+        "wagon",
+        "zone",
+    ];
+    const BAND: u64 = 10;
+    // Values below the first band or above the last are invalid.
+    const FIRST: u64 = 20;
+    const LAST: u64 = FIRST + BAND * FIELDS.len() as u64 - 1;
+
+    let mut ranks = (0..FIELDS.len()).collect::<Vec<_>>();
+    rng.shuffle(&mut ranks);
+    let mut columns = (0..FIELDS.len()).collect::<Vec<_>>();
+    rng.shuffle(&mut columns);
+    let band = |rank: usize| FIRST + BAND * rank as u64;
+
+    let mut input = String::new();
+    for (field, &rank) in FIELDS.iter().zip(&ranks) {
+        let split = rng.between(band(rank), LAST - 1);
+        writeln!(
+            input,
+            "{}: {}-{} or {}-{}",
+            field,
+            band(rank),
+            split,
+            split + 1,
+            LAST
+        )
+        .unwrap();
+    }
+
+    // A value from any band at or above the rank of the column's field, or
+    // from exactly that band.
+    let ticket = |rng: &mut Rng, exact: bool| {
+        columns
+            .iter()
+            .map(|&field| {
+                let rank = ranks[field];
+                let rank = if exact {
+                    rank
+                } else {
+                    rng.between(rank as u64, FIELDS.len() as u64 - 1) as usize
+                };
+                rng.between(band(rank), band(rank) + BAND - 1)
+            })
+            .collect::<Vec<_>>()
+    };
+    let join = |values: &[u64]| {
+        values
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+
+    let yours = ticket(rng, false);
+    writeln!(input, "\nyour ticket:\n{}\n\nnearby tickets:", join(&yours)).unwrap();
+    let mut invalid = 0;
+    for i in 0..size.max(1) {
+        let mut values = ticket(rng, i == 0);
+        if i > 0 && rng.chance(1, 4) {
+            for _ in 0..rng.between(1, 2) {
+                let value = if rng.chance(1, 2) {
+                    rng.between(1, FIRST - 1)
+                } else {
+                    rng.between(LAST + 1, 999)
+                };
+                let column = rng.index(values.len());
+                values[column] = value;
+            }
+            invalid += values
+                .iter()
+                .filter(|&&value| !(FIRST..=LAST).contains(&value))
+                .sum::<u64>();
+        }
+        writeln!(input, "{}", join(&values)).unwrap();
+    }
+
+    let departure = columns
+        .iter()
+        .zip(&yours)
+        .filter(|&(&field, _)| FIELDS[field].starts_with("departure"))
+        .map(|(_, &value)| value)
+        .product::<u64>();
+    Generated {
+        input,
+        answers: [invalid.to_string(), departure.to_string()],
+    }
+}
+
+/// Copies of the puzzle's glider, each turned and flipped at random and spaced
+/// far enough apart that they can't meet within six cycles, so each adds the
+/// example's 112 and 848 active cubes.
+fn day17(rng: &mut Rng, size: usize) -> Generated {
+    const GLIDER: [[bool; 3]; 3] = [
+        [false, true, false],
+        [false, false, true],
+        [true, true, true],
+    ];
+    // A glider grows by at most a cell a cycle on each side.
+    const SPACING: usize = 20;
+
+    let slots = (size / 10).clamp(1, 5);
+    let mut grid = vec![vec![false; slots * SPACING]; slots * SPACING];
+    let mut gliders = 0;
+    for slot in 0..slots * slots {
+        if slot > 0 && rng.chance(1, 2) {
+            continue;
+        }
+        gliders += 1;
+
+        let (flip, turns) = (rng.chance(1, 2), rng.between(0, 3));
+        for (y, row) in GLIDER.iter().enumerate() {
+            for (x, &active) in row.iter().enumerate() {
+                let (mut x, mut y) = (if flip { 2 - x } else { x }, y);
+                for _ in 0..turns {
+                    let turned = (2 - y, x);
+                    x = turned.0;
+                    y = turned.1;
+                }
+                grid[slot / slots * SPACING + y][slot % slots * SPACING + x] = active;
+            }
+        }
+    }
+
+    Generated {
+        input: lines(grid.iter().map(|row| {
+            row.iter()
+                .map(|&active| if active { '#' } else { '.' })
+                .collect::<String>()
+        })),
+        answers: [(gliders * 112).to_string(), (gliders * 848).to_string()],
+    }
+}
+
+/// Expressions built from random digits, operators and parentheses, with
+/// their value under both precedence rules worked out as they're built.
+fn day18(rng: &mut Rng, size: usize) -> Generated {
+    // Keeps the sum of every line well within 64 bits.
+    const LIMIT: u64 = 1 << 40;
+
+    /// An expression with its value when evaluating left to right, and when
+    /// additions come first. Values saturate rather than overflow.
+    fn expression(rng: &mut Rng, depth: usize) -> (String, u64, u64) {
+        let mut text = String::new();
+        let mut left_to_right = 0;
+        // Additions are summed into `sum`, which multiplies into `product`
+        // at every `*`.
+        let (mut product, mut sum) = (1u64, 0);
+        for i in 0..rng.between(2, 6) {
+            let (operand, left, added) = if depth < 2 && rng.chance(1, 4) {
+                let (text, left, added) = expression(rng, depth + 1);
+                (format!("({})", text), left, added)
+            } else {
+                let digit = rng.between(1, 9);
+                (digit.to_string(), digit, digit)
+            };
+
+            if i == 0 {
+                left_to_right = left;
+                sum = added;
+            } else if rng.chance(1, 2) {
+                text.push_str(" + ");
+                left_to_right = left_to_right.saturating_add(left);
+                sum = sum.saturating_add(added);
+            } else {
+                text.push_str(" * ");
+                left_to_right = left_to_right.saturating_mul(left);
+                product = product.saturating_mul(sum);
+                sum = added;
+            }
+            text.push_str(&operand);
+        }
+        (text, left_to_right, product.saturating_mul(sum))
+    }
+
+    let mut homework = vec![];
+    let (mut left_to_right, mut additions_first) = (0, 0);
+    while homework.len() < size.max(1) {
+        let (line, left, added) = expression(rng, 0);
+        if left <= LIMIT && added <= LIMIT {
+            homework.push(line);
+            left_to_right += left;
+            additions_first += added;
+        }
+    }
+
+    Generated {
+        input: lines(homework),
+        answers: [left_to_right.to_string(), additions_first.to_string()],
+    }
+}
+
+/// Rules where 42 and 31 each match a random half of the strings of `a` and
+/// `b` of some length, so every message splits into a unique sequence of
+/// them. Messages are built from such sequences, or made too short to split,
+/// and checked against the patterns each part accepts.
+fn day19(rng: &mut Rng, size: usize) -> Generated {
+    let len = rng.between(2, 4) as u32;
+    let mut blocks = (0..1u32 << len)
+        .map(|bits| {
+            (0..len)
+                .map(|i| if bits >> i & 1 == 1 { 'b' } else { 'a' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut blocks);
+    let (forty_two, thirty_one) = blocks.split_at(rng.between(1, (1 << len) - 1) as usize);
+
+    // Rule numbers other than the fixed ones, handed out at random.
+    let mut numbers = (1..200)
+        .filter(|number| ![8, 11, 31, 42].contains(number))
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut numbers);
+    let mut numbers = numbers.into_iter();
+    let (a, b) = (numbers.next().unwrap(), numbers.next().unwrap());
+    let mut rules = vec![
+        "0: 8 11".to_string(),
+        "8: 42".to_string(),
+        "11: 42 31".to_string(),
+        format!("{}: \"a\"", a),
+        format!("{}: \"b\"", b),
+    ];
+
+    /// Adds the rules of a trie matching `strings`, all of the same length,
+    /// under rule `number`. The last letter refers to the letter rules
+    /// directly, so no rule is only a reference to another reference.
+    fn trie(
+        rules: &mut Vec<String>,
+        numbers: &mut impl Iterator<Item = usize>,
+        number: usize,
+        strings: &[&str],
+        letters: (usize, usize),
+    ) {
+        let mut choices = vec![];
+        for (letter, rule) in [('a', letters.0), ('b', letters.1)].iter().copied() {
+            let rest = strings
+                .iter()
+                .filter(|string| string.starts_with(letter))
+                .map(|string| &string[1..])
+                .collect::<Vec<_>>();
+            if rest.is_empty() {
+                continue;
+            }
+            if rest[0].is_empty() {
+                choices.push(rule.to_string());
+            } else {
+                let child = numbers.next().unwrap();
+                trie(rules, numbers, child, &rest, letters);
+                choices.push(format!("{} {}", rule, child));
+            }
+        }
+        rules.push(format!("{}: {}", number, choices.join(" | ")));
+    }
+    for &(number, blocks) in &[(42, forty_two), (31, thirty_one)] {
+        let strings = blocks.iter().map(String::as_str).collect::<Vec<_>>();
+        trie(&mut rules, &mut numbers, number, &strings, (a, b));
+    }
+    rng.shuffle(&mut rules);
+
+    // Part 1 matches 42 42 31, and part 2 any number of 42s followed by
+    // fewer 31s.
+    let mut messages = vec![];
+    let mut matches = [0; 2];
+    for _ in 0..size.max(1) {
+        let (fronts, backs) = (rng.between(0, 4), rng.between(0, 3));
+        let mut message = String::new();
+        for i in 0..fronts + backs {
+            let blocks = if i < fronts { forty_two } else { thirty_one };
+            message.push_str(&blocks[rng.index(blocks.len())]);
+        }
+        if rng.chance(1, 5) {
+            message.pop();
+        } else {
+            matches[0] += (fronts == 2 && backs == 1) as usize;
+            matches[1] += (fronts > backs && backs >= 1) as usize;
+        }
+        if message.is_empty() {
+            continue;
+        }
+        messages.push(message);
+    }
+
+    Generated {
+        input: lines(rules) + "\n" + &lines(messages),
+        answers: [matches[0].to_string(), matches[1].to_string()],
+    }
+}
+
+#[test]
+fn test_planted_answers() {
+    for generator in GENERATORS {
+        let day = crate::runner::find(generator.day).unwrap();
+        // Day 15 plays thirty million turns each time.
+        let seeds = if generator.day == 15 { 2 } else { 20 };
+        for seed in 0..seeds {
+            let generated = generator.generate(seed, 60);
+            let report = day
+                .run(&generated.input, &crate::runner::Part::ALL)
+                .unwrap();
+            for (answer, expected) in report.answers.iter().zip(&generated.answers) {
+                assert_eq!(
+                    answer.answer.as_ref(),
+                    Ok(expected),
+                    "day {} part {} with seed {}:\n{}",
+                    generator.day,
+                    answer.part.number(),
+                    seed,
+                    generated.input
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_reproducible() {
+    let generator = find(8).unwrap();
+    assert_eq!(
+        generator.generate(7, 100).input,
+        generator.generate(7, 100).input
+    );
+    assert_ne!(
+        generator.generate(7, 100).input,
+        generator.generate(8, 100).input
+    );
+}
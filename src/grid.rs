@@ -0,0 +1,312 @@
+use crate::parse::{self, Line, ParseError};
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+/// The four orthogonal steps, as `(dx, dy)`.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The eight orthogonal and diagonal steps, as `(dx, dy)`.
+pub const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular map of cells stored row by row, addressed by `(x, y)` with
+/// the origin in the top left corner.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells in row order.
+    pub fn new(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "{} cells do not fill rows of width {}",
+            cells.len(),
+            width
+        );
+        Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// Parses a character map with one row per line. Every row must be as
+    /// wide as the first, and every character must convert into a cell;
+    /// `expected` describes the valid characters for error messages.
+    pub fn parse(day: u32, input: &str, expected: &str) -> Result<Self, ParseError>
+    where
+        T: TryFrom<char>,
+    {
+        let mut width = None;
+        let mut cells = vec![];
+        for line in parse::lines(day, input) {
+            let row_width = line.text().chars().count();
+            match width {
+                None if row_width == 0 => return Err(line.missing(expected)),
+                None => width = Some(row_width),
+                Some(width) if row_width != width => {
+                    return Err(line.error(
+                        line.text(),
+                        format!("a row of {} cells like the first", width),
+                    ))
+                }
+                Some(_) => {}
+            }
+            for (i, c) in line.text().char_indices() {
+                cells.push(T::try_from(c).map_err(|_| line.char_error(i, expected))?);
+            }
+        }
+
+        let width = width.ok_or_else(|| Line::new(day, 0, input).missing(expected))?;
+        Ok(Self::new(width, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Looks up a cell as if the grid repeated forever in every direction.
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self[(x, y)]
+    }
+
+    /// Steps from `(x, y)` by `(dx, dy)`, if that stays on the grid.
+    pub fn step(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let x = (x as isize).checked_add(dx)?;
+        let y = (y as isize).checked_add(dy)?;
+        if (0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y) {
+            Some((x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+
+    /// The positions one step away from `(x, y)` in each of `directions`,
+    /// skipping those that fall off the grid. See [`ORTHOGONAL`] and
+    /// [`ALL_DIRECTIONS`].
+    pub fn neighbors<'a>(
+        &'a self,
+        position: (usize, usize),
+        directions: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        directions
+            .iter()
+            .filter_map(move |&direction| self.step(position, direction))
+    }
+
+    /// The positions reached by repeatedly stepping from `(x, y)` by
+    /// `direction`, excluding the start, until leaving the grid.
+    pub fn ray(
+        &self,
+        position: (usize, usize),
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        assert_ne!(direction, (0, 0), "a ray needs a direction");
+        std::iter::successors(self.step(position, direction), move |&position| {
+            self.step(position, direction)
+        })
+    }
+
+    /// Every position on the grid in row order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// Every cell in row order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// The grid turned a quarter turn clockwise.
+    pub fn rotated(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|x| (0..self.height).rev().map(move |y| (x, y)))
+            .map(|position| self[position].clone())
+            .collect();
+        Grid::new(self.height, cells)
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flipped(&self) -> Self {
+        let cells = self
+            .rows()
+            .flat_map(|row| row.iter().rev().cloned())
+            .collect();
+        Grid::new(self.width, cells)
+    }
+
+    /// The eight ways the grid can be rotated and flipped, starting with the
+    /// grid as it is and then its clockwise rotations.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations = Vec::with_capacity(8);
+        orientations.push(self.clone());
+        for i in 1..8 {
+            orientations.push(match i {
+                4 => self.flipped(),
+                _ => orientations[i - 1].rotated(),
+            });
+        }
+        orientations
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "({}, {}) is outside a {}x{} grid",
+                x, y, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside a {}x{} grid", x, y, width, height))
+    }
+}
+
+/// Renders the grid back into the character map it was parsed from.
+impl<T> Display for Grid<T>
+where
+    T: Copy + Into<char>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(
+                f,
+                "{}",
+                row.iter().map(|&cell| cell.into()).collect::<String>()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct Cell(bool);
+
+#[cfg(test)]
+impl TryFrom<char> for Cell {
+    type Error = ();
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '#' => Ok(Cell(true)),
+            '.' => Ok(Cell(false)),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+impl From<Cell> for char {
+    fn from(cell: Cell) -> char {
+        if cell.0 {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+#[test]
+fn test_parse() {
+    let content = "#..
+.#.
+..#
+##.";
+    let grid = Grid::<Cell>::parse(0, content, "`#` or `.`").unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 4));
+    assert_eq!(grid[(1, 1)], Cell(true));
+    assert_eq!(grid.get(3, 0), None);
+    assert_eq!(*grid.get_wrapping(-1, 4), Cell(false));
+    assert_eq!(*grid.get_wrapping(4, -1), Cell(true));
+    assert_eq!(grid.to_string(), format!("{}\n", content));
+
+    let e = Grid::<Cell>::parse(0, "#.\n#", "`#` or `.`").unwrap_err();
+    assert_eq!(e.line(), 2);
+    let e = Grid::<Cell>::parse(0, "#.\n#x", "`#` or `.`").unwrap_err();
+    assert_eq!((e.line(), e.column()), (2, 2));
+}
+
+#[test]
+fn test_neighbors() {
+    let grid = Grid::new(3, vec![0; 9]);
+    assert_eq!(grid.neighbors((0, 0), &ORTHOGONAL).count(), 2);
+    assert_eq!(grid.neighbors((1, 1), &ORTHOGONAL).count(), 4);
+    assert_eq!(grid.neighbors((0, 1), &ALL_DIRECTIONS).count(), 5);
+    assert_eq!(grid.neighbors((1, 1), &ALL_DIRECTIONS).count(), 8);
+    assert_eq!(
+        grid.ray((0, 0), (1, 1)).collect::<Vec<_>>(),
+        vec![(1, 1), (2, 2)]
+    );
+    assert_eq!(grid.ray((2, 0), (1, 0)).next(), None);
+}
+
+#[test]
+fn test_orientations() {
+    let grid = Grid::new(3, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(grid.rotated(), Grid::new(2, vec![4, 1, 5, 2, 6, 3]));
+    assert_eq!(grid.flipped(), Grid::new(3, vec![3, 2, 1, 6, 5, 4]));
+    assert_eq!(grid.rotated().rotated().rotated().rotated(), grid);
+
+    let orientations = grid.orientations();
+    assert_eq!(orientations.len(), 8);
+    for (i, a) in orientations.iter().enumerate() {
+        assert!(orientations[i + 1..].iter().all(|b| a != b));
+    }
+}
@@ -1,5 +1,13 @@
 use aoc_runner_derive::aoc_lib;
 
+pub mod answers;
+pub mod bench;
+pub mod gen;
+pub mod grid;
+pub mod params;
+pub mod parse;
+pub mod runner;
+
 pub mod day1;
 pub mod day2;
 pub mod day3;
@@ -19,5 +27,11 @@ pub mod day16;
 pub mod day17;
 pub mod day18;
 pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
 
 aoc_lib! { year = 2020 }
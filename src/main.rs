@@ -1,3 +1,651 @@
-use aoc_runner_derive::aoc_main;
+use aoc_2020::answers::{self, Answers, Check, Table};
+use aoc_2020::bench::{self, Benchmark};
+use aoc_2020::day2;
+use aoc_2020::gen::{self, Generator};
+use aoc_2020::params::{Config, Params};
+use aoc_2020::runner::{self, Day, Outcome, Part, Summary};
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+use std::thread;
+use std::time::Instant;
 
-aoc_main! { lib = aoc_2020 }
+const USAGE: &str = "Usage:
+    aoc-2020 run --day <N> [--part <1|2>] [--input <path|->] [PARAMS]
+    aoc-2020 all [--threads <N>] [--config <path>]
+    aoc-2020 verify [--day <N>]
+    aoc-2020 check --day <N> [--part <1|2>] [--input <path|->] [PARAMS]
+    aoc-2020 bench [--day <N>] [--part <1|2>] [--input <path|->] [--iterations <N>] [--json]
+                   [--variants] [PARAMS]
+    aoc-2020 gen --day <N> [--seed <N>] [--size <N>] [--check]
+    aoc-2020 audit [--input <path|->] [--format <csv|json>]
+    aoc-2020 list
+
+Commands:
+    run     Solve a single day. Defaults to both parts and input/2020/day<N>.txt.
+            Pass `-` as the input to read from stdin.
+    all     Solve every registered day against its default input in parallel,
+            one thread per CPU by default, and print a summary table.
+    verify  Check every day, or a single one, against input/2020/answers.toml.
+    check   Solve a day with every registered variant of each part and check
+            that they agree.
+    bench   Time the generator and each part separately over several iterations
+            (10 by default), reporting min/median/p95. `--json` prints JSON
+            with timings in nanoseconds instead of a table, and `--variants`
+            times every variant of each part side by side.
+    gen     Print a synthetic input for a day (seed 0 and size 100 by default),
+            with its planted answers on stderr. `--check` solves it and compares
+            against the planted answers instead.
+    audit   Check every day 2 password against each policy interpretation and
+            print the verdicts with failure rates by pattern and bound width,
+            as CSV by default.
+    list    Print the registered days and the parameters they declare.
+
+Params:
+    --config <path>       Read parameter overrides from a TOML file with a
+                          `[day<N>]` table of `name = value` pairs per day.
+    --param <name=value>  Override a parameter of the day given with `--day`.
+                          May be repeated, and takes precedence over `--config`.";
+
+/// Parameter overrides from `--config` and `--param`.
+#[derive(Default)]
+struct Overrides {
+    config: Option<String>,
+    params: Vec<String>,
+}
+
+impl Overrides {
+    /// Handles `flag` if it is one of ours, returning whether it was.
+    fn parse_flag(&mut self, flag: &str, value: &str) -> bool {
+        match flag {
+            "--config" | "-c" => self.config = Some(value.into()),
+            "--param" | "-P" => self.params.push(value.into()),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Loads the config file, if any, and applies the `--param`s to `day`.
+    fn load(&self, day: Option<&Day>) -> Result<Config, String> {
+        let mut config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => Config::default(),
+        };
+        for param in &self.params {
+            let (name, value) = param
+                .split_once('=')
+                .ok_or_else(|| format!("expected `<name>=<value>`, found `{}`", param))?;
+            let day = day.ok_or("`--param` requires `--day`")?;
+            config.set(day.number(), name, value);
+        }
+        Ok(config)
+    }
+}
+
+enum Command {
+    Run {
+        day: &'static Day,
+        parts: Vec<Part>,
+        input: Option<String>,
+        overrides: Overrides,
+    },
+    All {
+        threads: usize,
+        overrides: Overrides,
+    },
+    Verify {
+        day: Option<&'static Day>,
+    },
+    Check {
+        day: &'static Day,
+        parts: Vec<Part>,
+        input: Option<String>,
+        overrides: Overrides,
+    },
+    Bench {
+        day: Option<&'static Day>,
+        parts: Vec<Part>,
+        input: Option<String>,
+        iterations: usize,
+        json: bool,
+        variants: bool,
+        overrides: Overrides,
+    },
+    Gen {
+        generator: &'static Generator,
+        seed: u64,
+        size: usize,
+        check: bool,
+    },
+    Audit {
+        input: Option<String>,
+        json: bool,
+    },
+    List,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    fn parse_day(value: &str) -> Result<&'static Day, String> {
+        let number = value
+            .parse()
+            .map_err(|_| format!("invalid day `{}`", value))?;
+        runner::find(number).ok_or_else(|| format!("day {} is not implemented", number))
+    }
+
+    fn parse_part(value: &str) -> Result<Part, String> {
+        value
+            .parse()
+            .ok()
+            .and_then(Part::from_number)
+            .ok_or_else(|| format!("invalid part `{}`", value))
+    }
+
+    let command = args.next().ok_or("missing command")?;
+    match command.as_str() {
+        "run" | "check" => {
+            let mut day = None;
+            let mut parts = Part::ALL.to_vec();
+            let mut input = None;
+            let mut overrides = Overrides::default();
+            while let Some(flag) = args.next() {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `{}`", flag))?;
+                match flag.as_str() {
+                    "--day" | "-d" => day = Some(parse_day(&value)?),
+                    "--part" | "-p" => parts = vec![parse_part(&value)?],
+                    "--input" | "-i" => input = Some(value),
+                    _ if overrides.parse_flag(&flag, &value) => {}
+                    _ => return Err(format!("unknown flag `{}`", flag)),
+                }
+            }
+
+            let day = day.ok_or("missing `--day`")?;
+            Ok(if command == "run" {
+                Command::Run {
+                    day,
+                    parts,
+                    input,
+                    overrides,
+                }
+            } else {
+                Command::Check {
+                    day,
+                    parts,
+                    input,
+                    overrides,
+                }
+            })
+        }
+        "verify" => {
+            let mut day = None;
+            while let Some(flag) = args.next() {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `{}`", flag))?;
+                match flag.as_str() {
+                    "--day" | "-d" => day = Some(parse_day(&value)?),
+                    _ => return Err(format!("unknown flag `{}`", flag)),
+                }
+            }
+
+            Ok(Command::Verify { day })
+        }
+        "bench" => {
+            let mut day = None;
+            let mut parts = Part::ALL.to_vec();
+            let mut input = None;
+            let mut iterations = 10;
+            let mut json = false;
+            let mut variants = false;
+            let mut overrides = Overrides::default();
+            while let Some(flag) = args.next() {
+                match flag.as_str() {
+                    "--json" => {
+                        json = true;
+                        continue;
+                    }
+                    "--variants" => {
+                        variants = true;
+                        continue;
+                    }
+                    _ => {}
+                }
+
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `{}`", flag))?;
+                match flag.as_str() {
+                    "--day" | "-d" => day = Some(parse_day(&value)?),
+                    "--part" | "-p" => parts = vec![parse_part(&value)?],
+                    "--input" | "-i" => input = Some(value),
+                    "--iterations" | "-n" => {
+                        iterations = value
+                            .parse()
+                            .ok()
+                            .filter(|&iterations| iterations > 0)
+                            .ok_or_else(|| format!("invalid iteration count `{}`", value))?;
+                    }
+                    _ if overrides.parse_flag(&flag, &value) => {}
+                    _ => return Err(format!("unknown flag `{}`", flag)),
+                }
+            }
+
+            if input.is_some() && day.is_none() {
+                return Err("`--input` requires `--day`".into());
+            }
+
+            Ok(Command::Bench {
+                day,
+                parts,
+                input,
+                iterations,
+                json,
+                variants,
+                overrides,
+            })
+        }
+        "gen" => {
+            let mut generator = None;
+            let mut seed = 0;
+            let mut size = 100;
+            let mut check = false;
+            while let Some(flag) = args.next() {
+                if flag == "--check" {
+                    check = true;
+                    continue;
+                }
+
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `{}`", flag))?;
+                match flag.as_str() {
+                    "--day" | "-d" => {
+                        let day = parse_day(&value)?;
+                        generator = Some(gen::find(day.number()).ok_or_else(|| {
+                            format!("day {} has no input generator", day.number())
+                        })?);
+                    }
+                    "--seed" | "-s" => {
+                        seed = value
+                            .parse()
+                            .map_err(|_| format!("invalid seed `{}`", value))?;
+                    }
+                    "--size" | "-n" => {
+                        size = value
+                            .parse()
+                            .map_err(|_| format!("invalid size `{}`", value))?;
+                    }
+                    _ => return Err(format!("unknown flag `{}`", flag)),
+                }
+            }
+
+            Ok(Command::Gen {
+                generator: generator.ok_or("missing `--day`")?,
+                seed,
+                size,
+                check,
+            })
+        }
+        "all" => {
+            let mut threads = thread::available_parallelism().map_or(1, |n| n.get());
+            let mut overrides = Overrides::default();
+            while let Some(flag) = args.next() {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `{}`", flag))?;
+                match flag.as_str() {
+                    "--threads" | "-j" => {
+                        threads = value
+                            .parse()
+                            .ok()
+                            .filter(|&threads| threads > 0)
+                            .ok_or_else(|| format!("invalid thread count `{}`", value))?;
+                    }
+                    "--config" | "-c" => overrides.config = Some(value),
+                    _ => return Err(format!("unknown flag `{}`", flag)),
+                }
+            }
+
+            Ok(Command::All { threads, overrides })
+        }
+        "audit" => {
+            let mut input = None;
+            let mut json = false;
+            while let Some(flag) = args.next() {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `{}`", flag))?;
+                match flag.as_str() {
+                    "--input" | "-i" => input = Some(value),
+                    "--format" | "-f" => {
+                        json = match value.as_str() {
+                            "csv" => false,
+                            "json" => true,
+                            _ => return Err(format!("invalid format `{}`", value)),
+                        };
+                    }
+                    _ => return Err(format!("unknown flag `{}`", flag)),
+                }
+            }
+
+            Ok(Command::Audit { input, json })
+        }
+        "list" => {
+            if let Some(arg) = args.next() {
+                return Err(format!("unexpected argument `{}`", arg));
+            }
+
+            Ok(Command::List)
+        }
+        _ => Err(format!("unknown command `{}`", command)),
+    }
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("could not read stdin: {}", e))?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path).map_err(|e| format!("could not read `{}`: {}", path, e))
+    }
+}
+
+/// Runs a day and prints its report, returning whether it succeeded.
+fn run(day: &Day, parts: &[Part], path: &str, params: Params) -> bool {
+    let input = match read_input(path) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Day {}: {}", day.number(), e);
+            return false;
+        }
+    };
+
+    match day.run_with(&input, parts, params) {
+        Ok(report) => {
+            println!("{}", report);
+            report.solved()
+        }
+        Err(e) => {
+            eprintln!("{}\n", e);
+            false
+        }
+    }
+}
+
+/// Solves a day with every variant and prints their answers side by side,
+/// returning whether they all agreed.
+fn check(day: &Day, parts: &[Part], path: &str, params: Params) -> bool {
+    let input = match read_input(path) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Day {}: {}", day.number(), e);
+            return false;
+        }
+    };
+
+    match day.cross_check(&input, parts, params) {
+        Ok(check) => {
+            print!("{}", check);
+            for &part in parts {
+                if !check.agrees(part) {
+                    eprintln!(
+                        "\nDay {} - Part {}: variants disagree",
+                        day.number(),
+                        part.number()
+                    );
+                }
+            }
+            check.all_agree()
+        }
+        Err(e) => {
+            eprintln!("{}\n", e);
+            false
+        }
+    }
+}
+
+fn main() {
+    let command = match parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let success = match command {
+        Command::Run {
+            day,
+            parts,
+            input,
+            overrides,
+        } => match overrides
+            .load(Some(day))
+            .and_then(|config| config.params(day.number(), day.params()))
+        {
+            Ok(params) => {
+                let path = input.unwrap_or_else(|| day.default_input_path());
+                run(day, &parts, &path, params)
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                false
+            }
+        },
+        Command::Check {
+            day,
+            parts,
+            input,
+            overrides,
+        } => match overrides
+            .load(Some(day))
+            .and_then(|config| config.params(day.number(), day.params()))
+        {
+            Ok(params) => {
+                let path = input.unwrap_or_else(|| day.default_input_path());
+                check(day, &parts, &path, params)
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                false
+            }
+        },
+        Command::All { threads, overrides } => {
+            let config = match overrides.load(None) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            };
+            let start = Instant::now();
+            let outcomes = runner::run_all(runner::DAYS, threads, &config, |day| {
+                read_input(&day.default_input_path())
+            });
+            let elapsed = start.elapsed();
+
+            println!("Advent of code 2020\n");
+            print!("{}", Summary(&outcomes));
+            for outcome in &outcomes {
+                match &outcome.result {
+                    Ok(report) => {
+                        for answer in &report.answers {
+                            if let Err(e) = &answer.answer {
+                                eprintln!(
+                                    "\nDay {} - Part {}: {}",
+                                    outcome.day,
+                                    answer.part.number(),
+                                    e
+                                );
+                            }
+                        }
+                    }
+                    Err(e) => eprintln!("\nDay {}: {}", outcome.day, e),
+                }
+            }
+            println!(
+                "\nSolved {} of {} days in {:.2?} on {} thread(s)",
+                outcomes.iter().filter(|outcome| outcome.solved()).count(),
+                outcomes.len(),
+                elapsed,
+                threads
+            );
+            outcomes.iter().all(Outcome::solved)
+        }
+        Command::Verify { day } => match Answers::load(answers::ANSWERS_PATH) {
+            Ok(known) => {
+                let days = match day {
+                    Some(day) => std::slice::from_ref(day),
+                    None => runner::DAYS,
+                };
+                let checks = days
+                    .iter()
+                    .flat_map(|day| {
+                        answers::verify(day, read_input(&day.default_input_path()), &known)
+                    })
+                    .collect::<Vec<_>>();
+                print!("{}", Table(&checks));
+                for check in &checks {
+                    if let Err(e) = &check.actual {
+                        eprintln!("\nDay {} - Part {}: {}", check.day, check.part.number(), e);
+                    }
+                }
+                checks.iter().all(|check| check.passed())
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                false
+            }
+        },
+        Command::Bench {
+            day,
+            parts,
+            input,
+            iterations,
+            json,
+            variants,
+            overrides,
+        } => {
+            let config = match overrides.load(day) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            };
+            let days = match day {
+                Some(day) => std::slice::from_ref(day),
+                None => runner::DAYS,
+            };
+            let mut success = true;
+            let mut benchmarks = vec![];
+            for day in days {
+                let path = input.clone().unwrap_or_else(|| day.default_input_path());
+                let result = config
+                    .params(day.number(), day.params())
+                    .and_then(|params| Ok((params, read_input(&path)?)))
+                    .and_then(|(params, input)| {
+                        Benchmark::run(day, &input, &parts, &params, iterations, variants)
+                            .map_err(|e| e.to_string())
+                    });
+                match result {
+                    Ok(benchmark) => benchmarks.push(benchmark),
+                    Err(e) => {
+                        eprintln!("Day {}: {}\n", day.number(), e);
+                        success = false;
+                    }
+                }
+            }
+
+            if json {
+                print!("{}", bench::Json(&benchmarks));
+            } else {
+                print!("{}", bench::Table(&benchmarks));
+            }
+            success
+        }
+        Command::Gen {
+            generator,
+            seed,
+            size,
+            check,
+        } => {
+            let generated = generator.generate(seed, size);
+            if check {
+                let day = runner::find(generator.day()).unwrap();
+                let actual = day
+                    .run(&generated.input, &Part::ALL)
+                    .map(|report| report.answers)
+                    .map_err(|e| e.to_string());
+                let checks = Part::ALL
+                    .iter()
+                    .zip(generated.answers.iter())
+                    .map(|(&part, expected)| Check {
+                        day: day.number(),
+                        part,
+                        expected: Some(expected.clone()),
+                        actual: match &actual {
+                            Ok(answers) => answers[part.number() as usize - 1].answer.clone(),
+                            Err(e) => Err(e.clone()),
+                        },
+                    })
+                    .collect::<Vec<_>>();
+                print!("{}", Table(&checks));
+                checks.iter().all(|check| check.passed())
+            } else {
+                print!("{}", generated.input);
+                for (part, answer) in Part::ALL.iter().zip(generated.answers.iter()) {
+                    eprintln!(
+                        "Day {} - Part {}: {}",
+                        generator.day(),
+                        part.number(),
+                        answer
+                    );
+                }
+                true
+            }
+        }
+        Command::Audit { input, json } => {
+            let path = input.unwrap_or_else(|| runner::find(2).unwrap().default_input_path());
+            let result = read_input(&path)
+                .and_then(|input| day2::try_input_generator(&input).map_err(|e| e.to_string()));
+            match result {
+                Ok(entries) => {
+                    let report = day2::AuditReport::new(&entries);
+                    if json {
+                        print!("{}", day2::Json(&report));
+                    } else {
+                        print!("{}", day2::Csv(&report));
+                    }
+                    true
+                }
+                Err(e) => {
+                    eprintln!("Day 2: {}", e);
+                    false
+                }
+            }
+        }
+        Command::List => {
+            for day in runner::DAYS {
+                println!("day{}", day.number());
+                for param in day.params() {
+                    println!(
+                        "    {} = {:?}: {}",
+                        param.name(),
+                        param.default(),
+                        param.help()
+                    );
+                }
+            }
+            true
+        }
+    };
+
+    if !success {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::str::FromStr;
+
+/// A puzzle constant that a day lets callers override, such as the target sum
+/// in day 1. Values are kept as strings and parsed when a solver reads them.
+pub struct Param {
+    name: &'static str,
+    default: &'static str,
+    help: &'static str,
+    check: fn(&str) -> bool,
+}
+
+fn parses_as<T: FromStr>(value: &str) -> bool {
+    value.parse::<T>().is_ok()
+}
+
+fn parses_at_least<const MIN: usize>(value: &str) -> bool {
+    value.parse::<usize>().is_ok_and(|value| value >= MIN)
+}
+
+impl Param {
+    /// Declares a parameter whose values must parse as `T`.
+    pub const fn new<T: FromStr>(
+        name: &'static str,
+        default: &'static str,
+        help: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            default,
+            help,
+            check: parses_as::<T>,
+        }
+    }
+
+    /// Declares a `usize` parameter whose values must be at least `MIN`, for
+    /// counts that the solver cannot work with when too small.
+    pub const fn at_least<const MIN: usize>(
+        name: &'static str,
+        default: &'static str,
+        help: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            default,
+            help,
+            check: parses_at_least::<MIN>,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn default(&self) -> &'static str {
+        self.default
+    }
+
+    pub fn help(&self) -> &'static str {
+        self.help
+    }
+}
+
+/// The values of a day's parameters: overrides where given, defaults
+/// otherwise.
+#[derive(Clone)]
+pub struct Params {
+    declared: &'static [Param],
+    overrides: BTreeMap<&'static str, String>,
+}
+
+impl Params {
+    pub fn defaults(declared: &'static [Param]) -> Self {
+        Self {
+            declared,
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    fn param(&self, name: &str) -> Option<&'static Param> {
+        self.declared.iter().find(|param| param.name == name)
+    }
+
+    /// Overrides `name`, which must be declared and given a valid value.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let param = self
+            .param(name)
+            .ok_or_else(|| format!("unknown parameter `{}`", name))?;
+        if !(param.check)(value) {
+            return Err(format!("invalid value `{}` for `{}`", value, name));
+        }
+
+        self.overrides.insert(param.name, value.into());
+        Ok(())
+    }
+
+    /// The value of `name`, which must be declared with type `T`.
+    pub fn get<T: FromStr>(&self, name: &str) -> T {
+        let param = self
+            .param(name)
+            .unwrap_or_else(|| panic!("parameter `{}` is not declared", name));
+        let value = self
+            .overrides
+            .get(name)
+            .map_or(param.default, String::as_str);
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("`{}` was declared with a different type", name))
+    }
+}
+
+/// Parameter overrides for any number of days, read from a config file like
+///
+/// ```toml
+/// [day9]
+/// preamble = 5
+/// ```
+///
+/// or given on the command line.
+#[derive(Default)]
+pub struct Config {
+    days: BTreeMap<u32, Vec<(String, String)>>,
+}
+
+impl Config {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let value = input
+            .parse::<toml::Value>()
+            .map_err(|e| format!("invalid config file: {}", e))?;
+        let table = value.as_table().ok_or("config must be a table")?;
+
+        let mut config = Self::default();
+        for (key, value) in table {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| format!("expected a `dayN` table, found `{}`", key))?;
+            let params = value
+                .as_table()
+                .ok_or_else(|| format!("`{}` must be a table", key))?;
+            for (name, value) in params {
+                let value = match value {
+                    toml::Value::String(value) => value.clone(),
+                    toml::Value::Integer(value) => value.to_string(),
+                    toml::Value::Float(value) => value.to_string(),
+                    toml::Value::Boolean(value) => value.to_string(),
+                    _ => return Err(format!("`{}.{}` must be a single value", key, name)),
+                };
+                config.set(day, name, &value);
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let input =
+            fs::read_to_string(path).map_err(|e| format!("could not read `{}`: {}", path, e))?;
+        Self::parse(&input)
+    }
+
+    /// Overrides `name` for `day`, replacing any earlier value.
+    pub fn set(&mut self, day: u32, name: &str, value: &str) {
+        let params = self.days.entry(day).or_default();
+        params.retain(|(existing, _)| existing != name);
+        params.push((name.into(), value.into()));
+    }
+
+    /// Applies the overrides for `day` to the defaults of `declared`.
+    pub fn params(&self, day: u32, declared: &'static [Param]) -> Result<Params, String> {
+        let mut params = Params::defaults(declared);
+        for (name, value) in self.days.get(&day).into_iter().flatten() {
+            params
+                .set(name, value)
+                .map_err(|e| format!("day {}: {}", day, e))?;
+        }
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+const TEST_PARAMS: &[Param] = &[
+    Param::at_least::<2>("preamble", "25", "numbers considered"),
+    Param::new::<String>("bag", "shiny gold", "the bag to look for"),
+];
+
+#[test]
+fn test_params() {
+    let mut params = Params::defaults(TEST_PARAMS);
+    assert_eq!(params.get::<usize>("preamble"), 25);
+    params.set("preamble", "5").unwrap();
+    assert_eq!(params.get::<usize>("preamble"), 5);
+    assert_eq!(params.get::<String>("bag"), "shiny gold");
+    assert!(params.set("preamble", "five").is_err());
+    assert!(params.set("preamble", "1").is_err());
+    assert!(params.set("preamble", "-2").is_err());
+    params.set("preamble", "2").unwrap();
+    assert!(params.set("cycles", "6").is_err());
+}
+
+#[test]
+fn test_config() {
+    let config = Config::parse(
+        r#"[day9]
+preamble = 5
+bag = "dark red"
+"#,
+    )
+    .unwrap();
+    let params = config.params(9, TEST_PARAMS).unwrap();
+    assert_eq!(params.get::<usize>("preamble"), 5);
+    assert_eq!(params.get::<String>("bag"), "dark red");
+    assert_eq!(
+        config
+            .params(1, TEST_PARAMS)
+            .unwrap()
+            .get::<usize>("preamble"),
+        25
+    );
+
+    let config = Config::parse("[day9]\ncycles = 6").unwrap();
+    assert_eq!(
+        config.params(9, TEST_PARAMS).err().unwrap(),
+        "day 9: unknown parameter `cycles`"
+    );
+}
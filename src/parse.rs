@@ -0,0 +1,227 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::str::FromStr;
+
+/// An error produced by a fallible input generator. Carries enough context
+/// to point at the exact spot in the input that could not be parsed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    day: u32,
+    line: usize,
+    column: usize,
+    source_line: String,
+    text: String,
+    expected: String,
+}
+
+impl ParseError {
+    /// Day of the generator that failed.
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// 1-based line number of the offending input.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// 1-based column (in chars) where the offending text starts.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The offending text itself. Empty if something was missing.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Description of what the generator expected to find.
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.text.is_empty() {
+            writeln!(
+                f,
+                "day {}, line {}, column {}: expected {}, found nothing",
+                self.day, self.line, self.column, self.expected
+            )?;
+        } else {
+            writeln!(
+                f,
+                "day {}, line {}, column {}: expected {}, found `{}`",
+                self.day, self.line, self.column, self.expected, self.text
+            )?;
+        }
+
+        let gutter = self.line.to_string().len();
+        let carets = self.text.chars().count().max(1);
+        writeln!(f, "{:gutter$} |", "", gutter = gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(
+            f,
+            "{:gutter$} | {:pad$}{}",
+            "",
+            "",
+            "^".repeat(carets),
+            gutter = gutter,
+            pad = self.column - 1
+        )
+    }
+}
+
+// The `aoc_main!` runner reports generator failures with `{:#?}`, so the
+// debug representation is the annotated snippet as well.
+impl Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Error for ParseError {}
+
+/// A single line of puzzle input, tagged with its day and position so that
+/// errors can be reported against it.
+#[derive(Copy, Clone)]
+pub struct Line<'a> {
+    day: u32,
+    index: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(day: u32, index: usize, text: &'a str) -> Self {
+        Self { day, index, text }
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Builds an error pointing at `token`, which must be a subslice of this
+    /// line (as produced by `split`, `trim`, regex captures, etc).
+    pub fn error(&self, token: &str, expected: impl Into<String>) -> ParseError {
+        let start = token.as_ptr() as usize;
+        let base = self.text.as_ptr() as usize;
+        assert!(
+            start >= base && start + token.len() <= base + self.text.len(),
+            "token must be a slice of the line"
+        );
+        let offset = start - base;
+        ParseError {
+            day: self.day,
+            line: self.index + 1,
+            column: self.text[..offset].chars().count() + 1,
+            source_line: self.text.into(),
+            text: token.into(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Builds an error pointing at the single char starting at byte `index`.
+    pub fn char_error(&self, index: usize, expected: impl Into<String>) -> ParseError {
+        let len = self.text[index..]
+            .chars()
+            .next()
+            .map(char::len_utf8)
+            .unwrap_or(0);
+        self.error(&self.text[index..index + len], expected)
+    }
+
+    /// Builds an error for something that should have followed the end of
+    /// this line.
+    pub fn missing(&self, expected: impl Into<String>) -> ParseError {
+        self.error(&self.text[self.text.len()..], expected)
+    }
+
+    /// Parses `token` (a subslice of this line), reporting `expected` on failure.
+    pub fn parse<T: FromStr>(&self, token: &str, expected: &str) -> Result<T, ParseError> {
+        token.parse().map_err(|_| self.error(token, expected))
+    }
+
+    /// Like `parse`, but for a token that may not exist.
+    pub fn parse_next<T: FromStr>(
+        &self,
+        token: Option<&str>,
+        expected: &str,
+    ) -> Result<T, ParseError> {
+        match token {
+            Some(token) => self.parse(token, expected),
+            None => Err(self.missing(expected)),
+        }
+    }
+}
+
+/// Iterates over the lines of `input` for the given day.
+pub fn lines(day: u32, input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(move |(index, text)| Line::new(day, index, text))
+}
+
+#[test]
+fn test_error_snippet() {
+    let line = Line::new(8, 2, "jmp +x4");
+    let err = line
+        .parse::<isize>(&line.text()[4..], "a signed integer")
+        .unwrap_err();
+    assert_eq!(err.line(), 3);
+    assert_eq!(err.column(), 5);
+    assert_eq!(
+        err.to_string(),
+        "day 8, line 3, column 5: expected a signed integer, found `+x4`
+  |
+3 | jmp +x4
+  |     ^^^"
+    );
+}
+
+#[test]
+fn test_missing_snippet() {
+    let line = Line::new(8, 0, "nop");
+    let err = line.parse_next::<isize>(None, "an argument").unwrap_err();
+    assert_eq!(err.column(), 4);
+    assert_eq!(err.text(), "");
+    assert_eq!(
+        err.to_string(),
+        "day 8, line 1, column 4: expected an argument, found nothing
+  |
+1 | nop
+  |    ^"
+    );
+}
+
+#[test]
+fn test_lines() {
+    let lines = lines(20, "Tile 1:\n\n#.#").collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[1].text(), "");
+    let err = lines[2].error(&lines[2].text()[1..], "a pixel");
+    assert_eq!((err.day(), err.line(), err.column()), (20, 3, 2));
+}
+
+#[test]
+fn test_char_error() {
+    // Columns count chars, so a multi-byte char before the error shifts
+    // the byte index but not the column.
+    let line = Line::new(24, 0, "é→x");
+    let err = line.char_error(5, "a direction");
+    assert_eq!((err.column(), err.text()), (3, "x"));
+    let err = line.char_error(2, "a direction");
+    assert_eq!((err.column(), err.text()), (2, "→"));
+
+    let err = line.char_error(line.text().len(), "a direction");
+    assert_eq!((err.column(), err.text()), (4, ""));
+}
+
+#[test]
+fn test_parse_out_of_range() {
+    let line = Line::new(22, 1, "256");
+    let err = line.parse::<u8>(line.text(), "a card").unwrap_err();
+    assert_eq!((err.line(), err.column(), err.text()), (2, 1, "256"));
+    assert_eq!(err.expected(), "a card");
+}
@@ -0,0 +1,752 @@
+use crate::params::{Config, Param, Params};
+use crate::parse::ParseError;
+use std::any::Any;
+use std::fmt::{self, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn from_number(number: u32) -> Option<Self> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// The name of the solution registered with `#[aoc]`, which every part has.
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// What a solver returns: an answer to print, or a `Result` whose error says
+/// why there is none.
+pub trait Solution {
+    fn into_answer(self) -> Result<String, String>;
+}
+
+macro_rules! plain_solutions {
+    ($($ty:ty),*) => {
+        $(
+            impl Solution for $ty {
+                fn into_answer(self) -> Result<String, String> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+plain_solutions!(u32, u64, u128, usize, i64, isize, String, &str);
+
+impl<T: Solution, E: Display> Solution for Result<T, E> {
+    fn into_answer(self) -> Result<String, String> {
+        self.map_err(|e| e.to_string())?.into_answer()
+    }
+}
+
+/// An alternative solution to one part of a day, solving the same generated
+/// input as the default one. Days list theirs in a `VARIANTS` static.
+pub struct Variant<T> {
+    pub name: &'static str,
+    pub part: Part,
+    pub solve: fn(&T, &Params) -> Result<String, String>,
+}
+
+/// A day's generated input, ready to be handed to either part's solver.
+pub trait Solve {
+    fn solve(&self, part: Part) -> Result<String, String>;
+
+    /// The names of every solution to `part`, starting with
+    /// [`DEFAULT_VARIANT`].
+    fn variants(&self, part: Part) -> Vec<&'static str>;
+
+    /// Solves `part` with the named solution, if it exists.
+    fn solve_variant(&self, part: Part, name: &str) -> Option<Result<String, String>>;
+}
+
+struct Parsed<T: 'static> {
+    input: T,
+    params: Params,
+    part1: fn(&T, &Params) -> Result<String, String>,
+    part2: fn(&T, &Params) -> Result<String, String>,
+    variants: &'static [Variant<T>],
+}
+
+impl<T> Solve for Parsed<T> {
+    fn solve(&self, part: Part) -> Result<String, String> {
+        match part {
+            Part::One => (self.part1)(&self.input, &self.params),
+            Part::Two => (self.part2)(&self.input, &self.params),
+        }
+    }
+
+    fn variants(&self, part: Part) -> Vec<&'static str> {
+        let variants = self.variants.iter().filter(|variant| variant.part == part);
+        Some(DEFAULT_VARIANT)
+            .into_iter()
+            .chain(variants.map(|variant| variant.name))
+            .collect()
+    }
+
+    fn solve_variant(&self, part: Part, name: &str) -> Option<Result<String, String>> {
+        if name == DEFAULT_VARIANT {
+            return Some(self.solve(part));
+        }
+
+        self.variants
+            .iter()
+            .find(|variant| variant.part == part && variant.name == name)
+            .map(|variant| (variant.solve)(&self.input, &self.params))
+    }
+}
+
+fn parsed<T: 'static>(
+    input: T,
+    params: Params,
+    part1: fn(&T, &Params) -> Result<String, String>,
+    part2: fn(&T, &Params) -> Result<String, String>,
+    variants: &'static [Variant<T>],
+) -> Box<dyn Solve> {
+    Box::new(Parsed {
+        input,
+        params,
+        part1,
+        part2,
+        variants,
+    })
+}
+
+/// Generates a day's input, to be solved with the given parameters.
+type Generator = fn(&str, Params) -> Result<Box<dyn Solve>, ParseError>;
+
+/// A registered puzzle day, dispatching to its module's `try_input_generator`
+/// and `solve_partN` functions, or `solve_partN_with` for days that declare
+/// `PARAMS`, along with any `VARIANTS`.
+pub struct Day {
+    number: u32,
+    params: &'static [Param],
+    generator: Generator,
+}
+
+impl Day {
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    /// The tunable constants this day declares.
+    pub fn params(&self) -> &'static [Param] {
+        self.params
+    }
+
+    pub fn default_params(&self) -> Params {
+        Params::defaults(self.params)
+    }
+
+    /// Where the puzzle input for this day lives, relative to the crate root.
+    pub fn default_input_path(&self) -> String {
+        format!("input/2020/day{}.txt", self.number)
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Solve>, ParseError> {
+        self.parse_with(input, self.default_params())
+    }
+
+    /// Generates the input, to be solved with the given parameters.
+    pub fn parse_with(&self, input: &str, params: Params) -> Result<Box<dyn Solve>, ParseError> {
+        // Match `aoc-runner`, which strips trailing newlines before generating.
+        (self.generator)(input.trim_end_matches('\n'), params)
+    }
+
+    /// Generates the input and solves the requested parts, timing each step.
+    pub fn run(&self, input: &str, parts: &[Part]) -> Result<Report, ParseError> {
+        self.run_with(input, parts, self.default_params())
+    }
+
+    /// Like [`Day::run`], but with parameters other than the defaults.
+    pub fn run_with(
+        &self,
+        input: &str,
+        parts: &[Part],
+        params: Params,
+    ) -> Result<Report, ParseError> {
+        let start = Instant::now();
+        let solver = self.parse_with(input, params)?;
+        let generator = start.elapsed();
+
+        let answers = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = solver.solve(part);
+                Answer {
+                    part,
+                    answer,
+                    runner: start.elapsed(),
+                }
+            })
+            .collect();
+
+        Ok(Report {
+            day: self.number,
+            generator,
+            answers,
+        })
+    }
+
+    /// Generates the input once and solves the requested parts with every
+    /// variant registered for them, timing each.
+    pub fn cross_check(
+        &self,
+        input: &str,
+        parts: &[Part],
+        params: Params,
+    ) -> Result<CrossCheck, ParseError> {
+        let solver = self.parse_with(input, params)?;
+        let answers = parts
+            .iter()
+            .flat_map(|&part| {
+                let solver = &solver;
+                solver.variants(part).into_iter().map(move |variant| {
+                    let start = Instant::now();
+                    let answer = solver.solve_variant(part, variant).unwrap();
+                    VariantAnswer {
+                        part,
+                        variant,
+                        answer,
+                        runner: start.elapsed(),
+                    }
+                })
+            })
+            .collect();
+
+        Ok(CrossCheck {
+            day: self.number,
+            answers,
+        })
+    }
+}
+
+pub struct Answer {
+    pub part: Part,
+    /// The answer, or why the part has none.
+    pub answer: Result<String, String>,
+    pub runner: Duration,
+}
+
+pub struct VariantAnswer {
+    pub part: Part,
+    pub variant: &'static str,
+    pub answer: Result<String, String>,
+    pub runner: Duration,
+}
+
+/// The answers of every variant of a day's parts to the same input.
+pub struct CrossCheck {
+    pub day: u32,
+    pub answers: Vec<VariantAnswer>,
+}
+
+impl CrossCheck {
+    /// Whether every variant of `part` gave the same answer.
+    pub fn agrees(&self, part: Part) -> bool {
+        let mut answers = self
+            .answers
+            .iter()
+            .filter(|answer| answer.part == part)
+            .map(|answer| &answer.answer);
+        match answers.next() {
+            Some(first) => answers.all(|answer| answer == first),
+            None => true,
+        }
+    }
+
+    pub fn all_agree(&self) -> bool {
+        Part::ALL.iter().all(|&part| self.agrees(part))
+    }
+}
+
+impl Display for CrossCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .answers
+            .iter()
+            .map(|answer| answer.variant.len())
+            .chain(Some("Variant".len()))
+            .max()
+            .unwrap();
+
+        writeln!(
+            f,
+            "Part | {:w$} | {:>10} | Answer",
+            "Variant",
+            "Time",
+            w = width
+        )?;
+        writeln!(f, "-----|-{:-<w$}-|------------|-------", "", w = width)?;
+        for answer in &self.answers {
+            writeln!(
+                f,
+                "{:>4} | {:w$} | {:>10} | {}{}",
+                answer.part.number(),
+                answer.variant,
+                format!("{:.2?}", answer.runner),
+                match &answer.answer {
+                    Ok(answer) => answer.clone(),
+                    Err(e) => format!("error: {}", e),
+                },
+                if self.agrees(answer.part) {
+                    ""
+                } else {
+                    "  MISMATCH"
+                },
+                w = width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The answers and timings for one run of a day.
+pub struct Report {
+    pub day: u32,
+    pub generator: Duration,
+    pub answers: Vec<Answer>,
+}
+
+impl Report {
+    /// Whether every part produced an answer.
+    pub fn solved(&self) -> bool {
+        self.answers.iter().all(|answer| answer.answer.is_ok())
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for answer in &self.answers {
+            match &answer.answer {
+                Ok(result) => writeln!(
+                    f,
+                    "Day {} - Part {}: {}\n\tgenerator: {:?},\n\trunner: {:?}",
+                    self.day,
+                    answer.part.number(),
+                    result,
+                    self.generator,
+                    answer.runner
+                )?,
+                Err(e) => writeln!(
+                    f,
+                    "Day {} - Part {}: error: {}",
+                    self.day,
+                    answer.part.number(),
+                    e
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The helpers below take the features listed in brackets after a day in
+// `days!`, which are some of `params` and `variants`, in that order.
+
+macro_rules! day_params {
+    ($day:ident; params $(, $rest:ident)*) => {
+        crate::$day::PARAMS
+    };
+    ($day:ident; $($rest:ident),*) => {
+        &[]
+    };
+}
+
+macro_rules! solver {
+    ($day:ident, $part:ident, $part_with:ident; params $(, $rest:ident)*) => {
+        |input, params| crate::$day::$part_with(input, params).into_answer()
+    };
+    ($day:ident, $part:ident, $part_with:ident; $($rest:ident),*) => {
+        |input, _| crate::$day::$part(input).into_answer()
+    };
+}
+
+macro_rules! day_variants {
+    ($day:ident; $(params,)? variants) => {
+        crate::$day::VARIANTS
+    };
+    ($day:ident; $($rest:ident),*) => {
+        &[]
+    };
+}
+
+macro_rules! days {
+    ($($number:literal => $day:ident $([$($feature:ident),+])?),* $(,)?) => {
+        /// Every implemented day, in order.
+        pub static DAYS: &[Day] = &[
+            $(
+                Day {
+                    number: $number,
+                    params: day_params!($day; $($($feature),+)?),
+                    generator: |input, params| {
+                        Ok(parsed(
+                            crate::$day::try_input_generator(input)?,
+                            params,
+                            solver!($day, solve_part1, solve_part1_with; $($($feature),+)?),
+                            solver!($day, solve_part2, solve_part2_with; $($($feature),+)?),
+                            day_variants!($day; $($($feature),+)?),
+                        ))
+                    },
+                },
+            )*
+        ];
+
+        /// Checks every day against its real input and accepted answers.
+        #[cfg(test)]
+        mod known_answers {
+            $(
+                #[test]
+                fn $day() {
+                    super::check_known_answers($number);
+                }
+            )*
+        }
+    };
+}
+
+days! {
+    1 => day1 [params, variants],
+    2 => day2,
+    3 => day3 [params],
+    4 => day4,
+    5 => day5,
+    6 => day6,
+    7 => day7 [params],
+    8 => day8,
+    9 => day9 [params],
+    10 => day10,
+    11 => day11,
+    12 => day12,
+    13 => day13 [variants],
+    14 => day14,
+    15 => day15 [params, variants],
+    16 => day16 [params],
+    17 => day17 [params],
+    18 => day18,
+    19 => day19,
+    20 => day20,
+    21 => day21,
+    22 => day22,
+    23 => day23 [params],
+    24 => day24 [params],
+    25 => day25,
+}
+
+/// Looks up a registered day by its number.
+pub fn find(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+/// How one day fared when running several at once.
+pub struct Outcome {
+    pub day: u32,
+    /// The report, or why the day could not be solved, including panics.
+    pub result: Result<Report, String>,
+}
+
+impl Outcome {
+    /// Whether the day ran and every part produced an answer.
+    pub fn solved(&self) -> bool {
+        self.result.as_ref().is_ok_and(Report::solved)
+    }
+}
+
+/// Solves both parts of every day in `days` on a pool of `threads` workers,
+/// reading each day's input with `input` and its parameters from `config`.
+/// A failure or panic in one day is recorded in its outcome without affecting
+/// the others. Outcomes are returned in the order of `days`.
+pub fn run_all<F>(days: &[Day], threads: usize, config: &Config, input: F) -> Vec<Outcome>
+where
+    F: Fn(&Day) -> Result<String, String> + Sync,
+{
+    fn panic_message(payload: Box<dyn Any + Send>) -> String {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic");
+        format!("panicked: {}", message)
+    }
+
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(Vec::with_capacity(days.len()));
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, days.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(day) = days.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        let params = config.params(day.number, day.params)?;
+                        let input = input(day)?;
+                        day.run_with(&input, &Part::ALL, params)
+                            .map_err(|e| e.to_string())
+                    }))
+                    .unwrap_or_else(|payload| Err(panic_message(payload)));
+                    outcomes.lock().unwrap().push(Outcome {
+                        day: day.number,
+                        result,
+                    });
+                }
+            });
+        }
+    });
+
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|outcome| {
+        days.iter()
+            .position(|day| day.number == outcome.day)
+            .unwrap()
+    });
+    outcomes
+}
+
+/// Renders outcomes as a table of answers and timings, one row per day.
+pub struct Summary<'a>(pub &'a [Outcome]);
+
+impl Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn answer(outcome: &Outcome, part: Part) -> (&str, String) {
+            match &outcome.result {
+                Ok(report) => report
+                    .answers
+                    .iter()
+                    .find(|answer| answer.part == part)
+                    .map_or(("-", "-".into()), |answer| match &answer.answer {
+                        Ok(result) => (result.as_str(), format!("{:.2?}", answer.runner)),
+                        Err(_) => ("error", format!("{:.2?}", answer.runner)),
+                    }),
+                Err(_) => ("-", "-".into()),
+            }
+        }
+
+        let width = |part| {
+            self.0
+                .iter()
+                .map(|outcome| answer(outcome, part).0.len())
+                .chain(Some("Part 1".len()))
+                .max()
+                .unwrap()
+        };
+        let (width1, width2) = (width(Part::One), width(Part::Two));
+
+        writeln!(
+            f,
+            "Day | {:w1$} | {:w2$} | {:>10} | {:>10} | {:>10} | Status",
+            "Part 1",
+            "Part 2",
+            "Generator",
+            "Part 1",
+            "Part 2",
+            w1 = width1,
+            w2 = width2
+        )?;
+        writeln!(
+            f,
+            "----|-{:-<w1$}-|-{:-<w2$}-|------------|------------|------------|-------",
+            "",
+            "",
+            w1 = width1,
+            w2 = width2
+        )?;
+        for outcome in self.0 {
+            let (answer1, time1) = answer(outcome, Part::One);
+            let (answer2, time2) = answer(outcome, Part::Two);
+            let (generator, status) = match &outcome.result {
+                Ok(report) if report.solved() => (format!("{:.2?}", report.generator), "ok"),
+                Ok(report) => (format!("{:.2?}", report.generator), "FAILED"),
+                Err(_) => ("-".into(), "FAILED"),
+            };
+            writeln!(
+                f,
+                "{:>3} | {:w1$} | {:w2$} | {:>10} | {:>10} | {:>10} | {}",
+                outcome.day,
+                answer1,
+                answer2,
+                generator,
+                time1,
+                time2,
+                status,
+                w1 = width1,
+                w2 = width2
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn check_known_answers(number: u32) {
+    use crate::answers::{self, Answers, Table};
+
+    let day = find(number).unwrap();
+    let path = day.default_input_path();
+    if !std::path::Path::new(&path).exists() {
+        eprintln!("skipping day {}: `{}` does not exist", number, path);
+        return;
+    }
+
+    let answers = Answers::load(answers::ANSWERS_PATH).unwrap();
+    let input = std::fs::read_to_string(&path).map_err(|e| e.to_string());
+    let checks = answers::verify(day, input, &answers);
+    assert!(
+        checks.iter().all(|check| check.passed()),
+        "\n{}",
+        Table(&checks)
+    );
+}
+
+/// A test per part for every fixture in `input/2020/examples`, generated by
+/// `build.rs`.
+#[cfg(test)]
+mod examples {
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+}
+
+#[cfg(test)]
+fn check_example(number: u32, input: &str, part: Part, expected: &str) {
+    let report = find(number).unwrap().run(input, &[part]).unwrap();
+    assert_eq!(report.answers[0].answer.as_deref(), Ok(expected));
+}
+
+#[test]
+fn test_cross_check() {
+    let day = find(1).unwrap();
+    let check = day
+        .cross_check(
+            "1721\n979\n366\n299\n675\n1456",
+            &Part::ALL,
+            day.default_params(),
+        )
+        .unwrap();
+    let variants = check
+        .answers
+        .iter()
+        .map(|answer| {
+            let result = answer.answer.as_deref().unwrap();
+            (answer.part.number(), answer.variant, result)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variants,
+        [
+            (1, DEFAULT_VARIANT, "514579"),
+            (1, "hashing", "514579"),
+            (2, DEFAULT_VARIANT, "241861950"),
+            (2, "hashing", "241861950"),
+        ]
+    );
+    assert!(check.all_agree());
+
+    let mut check = check;
+    check.answers[3].answer = Ok("0".into());
+    assert!(check.agrees(Part::One));
+    assert!(!check.agrees(Part::Two));
+    assert!(check.to_string().contains("MISMATCH"));
+}
+
+#[test]
+fn test_run_all() {
+    let days = &DAYS[7..9];
+    let outcomes = run_all(days, 2, &Config::default(), |day| match day.number() {
+        8 => Ok("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6".into()),
+        _ => panic!("no input for day {}", day.number()),
+    });
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[0].day, 8);
+    let report = outcomes[0].result.as_ref().unwrap();
+    assert_eq!(report.answers[1].answer.as_deref(), Ok("8"));
+    assert_eq!(outcomes[1].day, 9);
+    assert_eq!(
+        outcomes[1].result.as_ref().err().unwrap(),
+        "panicked: no input for day 9"
+    );
+}
+
+#[test]
+fn test_run_with_params() {
+    let day = find(9).unwrap();
+    let content =
+        "35\n20\n15\n25\n47\n40\n62\n55\n65\n95\n102\n117\n150\n182\n127\n219\n299\n277\n309\n576";
+    let mut params = day.default_params();
+    params.set("preamble", "5").unwrap();
+    let report = day.run_with(content, &Part::ALL, params).unwrap();
+    assert_eq!(report.answers[0].answer.as_deref(), Ok("127"));
+    assert_eq!(report.answers[1].answer.as_deref(), Ok("62"));
+}
+
+#[test]
+fn test_run() {
+    let day = find(8).unwrap();
+    let content = "nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+";
+    let report = day.run(content, &Part::ALL).unwrap();
+    assert_eq!(report.day, 8);
+    assert_eq!(report.answers[0].answer.as_deref(), Ok("5"));
+    assert_eq!(report.answers[1].answer.as_deref(), Ok("8"));
+}
+
+#[test]
+fn test_run_error() {
+    let day = find(1).unwrap();
+    let report = day.run("1\n2", &[Part::One]).unwrap();
+    assert!(!report.solved());
+    assert_eq!(
+        report.to_string(),
+        "Day 1 - Part 1: error: no 2 entries sum to 2020\n"
+    );
+
+    let report = day
+        .run("4611686018427387904\n-4611686018427385884", &[Part::One])
+        .unwrap();
+    assert!(!report.solved());
+    assert_eq!(
+        report.to_string(),
+        "Day 1 - Part 1: error: the product -4611686018427385884 * 4611686018427387904 \
+         overflows a 64-bit integer\n"
+    );
+}
+
+#[test]
+fn test_solution() {
+    assert_eq!(42u32.into_answer(), Ok("42".into()));
+    assert_eq!("-".into_answer(), Ok("-".into()));
+    assert_eq!(Ok::<u64, String>(7).into_answer(), Ok("7".into()));
+    assert_eq!(
+        Err::<u32, _>("no answer").into_answer(),
+        Err("no answer".into())
+    );
+}